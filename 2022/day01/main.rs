@@ -0,0 +1,11 @@
+use std::io;
+
+use day1::top_calorie_sum;
+
+fn main() {
+    let lines: Vec<String> = io::stdin().lines()
+                                .map_while(Result::ok).collect();
+
+    println!("Part 1: {}", top_calorie_sum(&lines, 1));
+    println!("Part 2: {}", top_calorie_sum(&lines, 3));
+}
@@ -1,12 +1,11 @@
 #[macro_use]
 extern crate lazy_static;
 
-use std::io;
 use std::str::FromStr;
 use std::collections::HashMap;
 
 #[derive(Copy,Clone,PartialEq,Eq,Hash)]
-enum Move {
+pub enum Move {
     Rock = 1,
     Paper,
     Scissors,
@@ -27,7 +26,7 @@ impl FromStr for Move {
 }
 
 #[derive(Copy,Clone,PartialEq,Eq)]
-enum GameResult {
+pub enum GameResult {
     Win = 6,
     Draw = 3,
     Lose = 0,
@@ -52,7 +51,7 @@ lazy_static! {
     ].iter().cloned().collect();
 }
 
-fn part1(rounds: &Vec<String>) -> i32 {
+pub fn part1(rounds: &[String]) -> i32 {
      rounds.iter()
         .map(|round| -> i32 {
         let opponent_move = Move::from_str(&round[0..1]).expect("Couldn't parse opponent move");
@@ -64,7 +63,7 @@ fn part1(rounds: &Vec<String>) -> i32 {
     }).sum()
 }
 
-fn move_for_result_against(m: Move, r: GameResult) -> Move {
+pub fn move_for_result_against(m: Move, r: GameResult) -> Move {
     match r {
         GameResult::Draw => m,
         GameResult::Win => MOVE_THAT_BEATS[&m],
@@ -75,7 +74,7 @@ fn move_for_result_against(m: Move, r: GameResult) -> Move {
     }
 }
 
-fn part2(rounds: &Vec<String>) -> i32 {
+pub fn part2(rounds: &[String]) -> i32 {
      rounds.iter()
         .map(|round| -> i32 {
         let opponent_move = Move::from_str(&round[0..1]).expect("Couldn't parse opponent move");
@@ -84,11 +83,3 @@ fn part2(rounds: &Vec<String>) -> i32 {
         player_move as i32 + target_result as i32
     }).sum()
 }
-
-fn main() {
-    let rounds: Vec<String> = io::stdin().lines()
-                                .filter_map(Result::ok).collect();
-
-    println!("Part 1: {:?}", part1(&rounds));
-    println!("Part 2: {:?}", part2(&rounds));
-}
@@ -0,0 +1,11 @@
+use std::io;
+
+use day2::{part1, part2};
+
+fn main() {
+    let rounds: Vec<String> = io::stdin().lines()
+                                .map_while(Result::ok).collect();
+
+    println!("Part 1: {:?}", part1(&rounds));
+    println!("Part 2: {:?}", part2(&rounds));
+}
@@ -1,7 +1,6 @@
-use std::io;
 use std::collections::HashSet;
 
-fn priority(item: char) -> u32 {
+pub fn priority(item: char) -> u32 {
     match item {
         'a'..='z' => item as u32 - 'a' as u32 + 1,
         'A'..='Z' => item as u32 - 'A' as u32 + 27,
@@ -9,17 +8,17 @@ fn priority(item: char) -> u32 {
     }
 }
 
-fn part1(sacks: &Vec<String>) -> u32 {
+pub fn part1(sacks: &[String]) -> u32 {
     sacks.iter().map(|sack| {
         let pocket_size = sack.len() / 2;
-        let first_pocket_items: HashSet<char> = (&sack[0..pocket_size]).chars().collect();
-        let second_pocket_items: HashSet<char> = (&sack[pocket_size..sack.len()]).chars().collect();
+        let first_pocket_items: HashSet<char> = sack[0..pocket_size].chars().collect();
+        let second_pocket_items: HashSet<char> = sack[pocket_size..sack.len()].chars().collect();
         *first_pocket_items.intersection(&second_pocket_items)
             .next().expect("Couldn't find duplicate item")
     }).map(priority).sum()
 }
 
-fn part2(sacks: &Vec<String>) -> u32 {
+pub fn part2(sacks: &[String]) -> u32 {
     sacks.chunks(3)
         .map(|group| {
             group.iter()
@@ -29,10 +28,3 @@ fn part2(sacks: &Vec<String>) -> u32 {
                 .expect("Couldn't find group item")
         }).map(priority).sum()
 }
-
-fn main() {
-    let sacks: Vec<String> = io::stdin().lines()
-                                .filter_map(Result::ok).collect();
-    println!("Part 1: {}", part1(&sacks));
-    println!("Part 2: {}", part2(&sacks));
-}
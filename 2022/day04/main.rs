@@ -0,0 +1,10 @@
+use std::io;
+
+use day4::{part1, part2};
+
+fn main() {
+    let lines: Vec<String> = io::stdin().lines()
+                                .map_while(Result::ok).collect();
+    println!("Part 1: {:?}", part1(&lines));
+    println!("Part 2: {}", part2(&lines));
+}
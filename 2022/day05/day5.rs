@@ -1,7 +1,7 @@
-use std::io;
+use std::fmt;
 use std::str;
 
-fn transpose<T: Copy>(v: Vec<Vec<T>>, default_val: T) -> Vec<Vec<T>> {
+pub fn transpose<T: Copy>(v: Vec<Vec<T>>, default_val: T) -> Vec<Vec<T>> {
     assert!(!v.is_empty());
 
     let len = v.iter().map(Vec::<_>::len).max().expect("Couldn't get transpose width");
@@ -17,7 +17,7 @@ fn transpose<T: Copy>(v: Vec<Vec<T>>, default_val: T) -> Vec<Vec<T>> {
         .collect()
 }
 
-fn parse_crates(crates: &Vec<String>) -> Vec<Vec<char>> {
+pub fn parse_crates(crates: &[String]) -> Vec<Vec<char>> {
     let mut cratevecs:Vec<_> = crates.iter()
         // First line is the top of the stack
         .rev()
@@ -28,82 +28,237 @@ fn parse_crates(crates: &Vec<String>) -> Vec<Vec<char>> {
                 .map(str::from_utf8)
                 .filter_map(Result::ok)
                 .map(|cratestr|
-                     match cratestr
+                     cratestr
                            .trim_matches(&[' ', '[', ']'] as &[_])
-                           .chars().nth(0) {
-                         Some(c) => c,
-                         _ => '\0',
-                     })
+                           .chars().next().unwrap_or('\0'))
                 .collect::<Vec<char>>()
         }).collect();
     // Transpose so our stack columns are their own Vecs we can push to/pop from
     cratevecs = transpose(cratevecs, '\0');
     cratevecs.iter_mut().for_each(|stack| stack.retain(|c| *c != '\0'));
-    return cratevecs;
+    // Stacks that start out empty only show up in the indices line
+    let num_stacks = crates.last().expect("Couldn't get indices line")
+                           .split_whitespace().count();
+    cratevecs.resize(num_stacks.max(cratevecs.len()), Vec::new());
+    cratevecs
 }
 
-fn parse_moves(moves: &Vec<String>) -> Vec<Vec<usize>> {
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub count: usize,
+    // Stacks are numbered from 1, like in the drawing
+    pub from: usize,
+    pub to: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    NoSuchStack { step: usize, stack: usize },
+    Underflow { step: usize, mv: Move, available: usize },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::NoSuchStack { step, stack } =>
+                write!(f, "Step {}: there's no stack {}", step, stack),
+            MoveError::Underflow { step, mv, available } =>
+                write!(f, "Step {}: can't {} with only {} crates there", step, mv, available),
+        }
+    }
+}
+
+// What sets the crane models apart is the order the lifted crates go down in
+pub trait Crane {
+    fn name(&self) -> &str;
+    // Takes the crates lifted off a stack, bottom first, and puts them in
+    // the order they'll be stacked in
+    fn arrange(&self, lifted: &mut [char]);
+}
+
+// Moves one crate at a time, so the lifted crates end up reversed
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> &str {
+        "CrateMover 9000"
+    }
+
+    fn arrange(&self, lifted: &mut [char]) {
+        lifted.reverse();
+    }
+}
+
+// Moves all the crates at once, keeping their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> &str {
+        "CrateMover 9001"
+    }
+
+    fn arrange(&self, _lifted: &mut [char]) {}
+}
+
+pub fn parse_moves(moves: &[String]) -> Vec<Move> {
     moves.iter().map(|movestr| {
-        movestr.split_whitespace()
+        let nums: Vec<usize> = movestr.split_whitespace()
                 .map(str::parse::<usize>)
                 .filter_map(Result::ok)
-                .collect()
+                .collect();
+        assert_eq!(nums.len(), 3, "Couldn't parse move {}", movestr);
+        Move { count: nums[0], from: nums[1], to: nums[2] }
     }).collect()
 }
 
-fn part1(mut crates: Vec<Vec<char>>, moves: &Vec<Vec<usize>>) -> String {
-    for move_vec in moves {
-        assert_eq!(move_vec.len(), 3);
+pub fn parse(lines: &[String]) -> (Vec<Vec<char>>, Vec<Move>) {
+    let mut line_regions: Vec<&[String]> = lines.split(String::is_empty).collect();
+    assert_eq!(line_regions.len(), 2);
+    let moves_strs = line_regions.pop().expect("Couldn't get moves");
+    let crates_strs = line_regions.pop().expect("Couldn't get crates");
+
+    (parse_crates(crates_strs), parse_moves(moves_strs))
+}
 
-        let mut popped_crates =
-            (0..move_vec[0]).map(|_| {
-                crates[move_vec[1] - 1].pop().expect("Couldn't pop crate")
-            }).collect::<Vec<char>>();
-        crates[move_vec[2] - 1].append(&mut popped_crates);
+// Checks the move against the stacks before touching them, so a bad move
+// leaves them as they were. Steps count from 1 for error messages.
+pub fn apply_move(crane: &dyn Crane, crates: &mut [Vec<char>], step: usize, mv: &Move)
+    -> Result<(), MoveError> {
+    for stack in [mv.from, mv.to] {
+        if !(1..=crates.len()).contains(&stack) {
+            return Err(MoveError::NoSuchStack { step, stack });
+        }
     }
-    crates.iter()
-        .map(|crate_stack| crate_stack.last()
-                             .copied().expect("Couldn't get top of crate stack"))
-        .collect::<String>()
+    let from = &mut crates[mv.from - 1];
+    if from.len() < mv.count {
+        return Err(MoveError::Underflow { step, mv: *mv, available: from.len() });
+    }
+    let mut lifted = from.split_off(from.len() - mv.count);
+    crane.arrange(&mut lifted);
+    crates[mv.to - 1].append(&mut lifted);
+    Ok(())
 }
 
-fn part2(mut crates: Vec<Vec<char>>, moves: &Vec<Vec<usize>>) -> String {
-    for move_vec in moves {
-        assert_eq!(move_vec.len(), 3);
-        let mut popped_crates: Vec<char> =
-            (0..move_vec[0]).map(|_| {
-                crates[move_vec[1] - 1].pop().expect("Couldn't pop crate")
-            })
-            .collect::<Vec<char>>()
-            .into_iter().rev().collect();
-        crates[move_vec[2] - 1].append(&mut popped_crates);
+// Carries out every move, calling on_step with the stacks after each one
+pub fn run_crane<F: FnMut(usize, &[Vec<char>])>(crane: &dyn Crane,
+                                                 mut crates: Vec<Vec<char>>,
+                                                 moves: &[Move],
+                                                 mut on_step: F)
+    -> Result<Vec<Vec<char>>, MoveError> {
+    for (i, mv) in moves.iter().enumerate() {
+        apply_move(crane, &mut crates, i + 1, mv)?;
+        on_step(i + 1, &crates);
     }
+    Ok(crates)
+}
+
+// Draws the stacks the same way the input does, numbers underneath. Every
+// line is as wide as the numbers line, padded with spaces like the input.
+pub fn render_crates(crates: &[Vec<char>]) -> String {
+    let height = crates.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height).rev()
+        .map(|level| {
+            crates.iter()
+                .map(|stack| stack.get(level)
+                                  .map_or("   ".to_string(), |c| format!("[{}]", c)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }).collect();
+    lines.push((1..=crates.len())
+                   .map(|i| format!("{:^3}", i))
+                   .collect::<Vec<_>>()
+                   .join(" "));
+    lines.join("\n") + "\n"
+}
+
+// The crate on top of each stack, leaving out empty stacks
+pub fn tops(crates: &[Vec<char>]) -> String {
     crates.iter()
-        .map(|crate_stack| crate_stack.last()
-                             .copied().expect("Couldn't get top of crate stack"))
+        .filter_map(|crate_stack| crate_stack.last().copied())
         .collect::<String>()
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines()
-                                .filter_map(Result::ok).collect();
+pub fn part1(crates: Vec<Vec<char>>, moves: &[Move]) -> Result<String, MoveError> {
+    run_crane(&CrateMover9000, crates, moves, |_, _| ()).map(|crates| tops(&crates))
+}
 
-    let mut line_regions: Vec<Vec<String>> =
-        lines.split(String::is_empty)
-             .map(|region| region.iter()
-                             .map(String::clone)
-                             .collect()
-             ).collect();
-    assert_eq!(line_regions.len(), 2);
-    let moves_strs: Vec<String> = line_regions.pop().expect("Couldn't get moves");
-    let crates_strs: Vec<String> = line_regions.pop().expect("Couldn't get crates");
+pub fn part2(crates: Vec<Vec<char>>, moves: &[Move]) -> Result<String, MoveError> {
+    run_crane(&CrateMover9001, crates, moves, |_, _| ()).map(|crates| tops(&crates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    // The example's drawing as it comes in the puzzle input, trailing
+    // spaces and all
+    static DRAWING: &str = "    [D]    \n\
+                            [N] [C]    \n\
+                            [Z] [M] [P]\n\
+                            \x201   2   3 \n";
+
+    #[test]
+    fn example() {
+        let (crates, moves) = parse(&to_lines(include_str!("example.txt")));
+        assert_eq!(part1(crates.to_vec(), &moves), Ok("CMZ".to_string()));
+        assert_eq!(part2(crates, &moves), Ok("MCD".to_string()));
+    }
+
+    #[test]
+    fn rendering_round_trips() {
+        let crates = parse_crates(&to_lines(DRAWING));
+        assert_eq!(crates, [vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+        assert_eq!(render_crates(&crates), DRAWING);
+
+        // Empty stacks, including at the end, still take up their width
+        let crates = vec![vec![], vec!['A', 'B', 'C'], vec!['D'], vec![]];
+        let rendered = render_crates(&crates);
+        assert_eq!(rendered,
+                   "    [C]        \n\
+                    \x20   [B]        \n\
+                    \x20   [A] [D]    \n\
+                    \x201   2   3   4 \n");
+        assert!(rendered.lines().all(|line| line.len() == 15));
+        assert_eq!(parse_crates(&to_lines(&rendered)), crates);
 
-    let crates = parse_crates(&crates_strs);
-    let moves = parse_moves(&moves_strs);
+        let (crates, moves) = parse(&to_lines(include_str!("example.txt")));
+        run_crane(&CrateMover9000, crates, &moves, |_, crates| {
+            assert_eq!(parse_crates(&to_lines(&render_crates(crates))), crates);
+        }).unwrap();
+    }
+
+    #[test]
+    fn bad_moves() {
+        let crates = parse_crates(&to_lines(DRAWING));
+        let moves = parse_moves(&to_lines("move 1 from 2 to 1\nmove 4 from 1 to 3"));
+        assert_eq!(part1(crates.to_vec(), &moves),
+                   Err(MoveError::Underflow { step: 2, mv: moves[1], available: 3 }));
+        assert_eq!(part2(crates.to_vec(), &moves).unwrap_err().to_string(),
+                   "Step 2: can't move 4 from 1 to 3 with only 3 crates there");
 
-    //println!("Starting crates: {:?}", crates);
-    //println!("Moves: {:?}", moves);
+        // Nothing moves when a move fails
+        let mut stacks = crates.to_vec();
+        let mv = Move { count: 3, from: 1, to: 2 };
+        assert_eq!(apply_move(&CrateMover9001, &mut stacks, 1, &mv),
+                   Err(MoveError::Underflow { step: 1, mv, available: 2 }));
+        assert_eq!(stacks, crates);
 
-    println!("Part 1: {}", part1(crates.to_vec(), &moves));
-    println!("Part 2: {}", part2(crates.to_vec(), &moves));
+        let mv = Move { count: 1, from: 3, to: 4 };
+        assert_eq!(apply_move(&CrateMover9000, &mut stacks, 5, &mv),
+                   Err(MoveError::NoSuchStack { step: 5, stack: 4 }));
+        let mv = Move { count: 1, from: 0, to: 1 };
+        assert_eq!(apply_move(&CrateMover9000, &mut stacks, 6, &mv),
+                   Err(MoveError::NoSuchStack { step: 6, stack: 0 }));
+        assert_eq!(stacks, crates);
+    }
 }
@@ -0,0 +1,49 @@
+use std::env;
+use std::io;
+use std::process;
+
+use day5::{parse, part1, part2, render_crates, run_crane, Crane, CrateMover9000, CrateMover9001};
+
+fn main() {
+    // `show=N` draws the stacks after N moves with each crane, 0 being the
+    // starting drawing
+    let show_step = env::args().skip(1)
+                               .find_map(|arg| arg.strip_prefix("show=").map(str::to_string))
+                               .map(|step| step.parse::<usize>().expect("Couldn't parse step to show"));
+
+    let lines: Vec<String> = io::stdin().lines()
+                                .map_while(Result::ok).collect();
+
+    let (crates, moves) = parse(&lines);
+
+    if let Some(show_step) = show_step {
+        assert!(show_step <= moves.len(), "There are only {} moves", moves.len());
+        let cranes: [&dyn Crane; 2] = [&CrateMover9000, &CrateMover9001];
+        for crane in cranes {
+            eprintln!("{} after {} moves:", crane.name(), show_step);
+            if show_step == 0 {
+                eprint!("{}", render_crates(&crates));
+            }
+            run_crane(crane, crates.to_vec(), &moves[..show_step], |step, crates| {
+                if step == show_step {
+                    eprint!("{}", render_crates(crates));
+                }
+            }).unwrap_or_else(|err| panic!("{}", err));
+        }
+    }
+
+    // Failed parts are reported on stderr, and the exit code tells the runner
+    let mut failed = false;
+    for (part, result) in [(1, part1(crates.to_vec(), &moves)), (2, part2(crates.to_vec(), &moves))] {
+        match result {
+            Ok(tops) => println!("Part {}: {}", part, tops),
+            Err(err) => {
+                eprintln!("Part {} failed: {}", part, err);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,12 @@
+use std::io;
+
+use day6::helper;
+
+fn main() {
+    let line: String = io::stdin().lines().next()
+                        .expect("Couldn't read input line").expect("Couldn't read input line")
+                        .trim().to_string();
+
+    println!("Part 1: {}", helper(&line, 4));
+    println!("Part 2: {}", helper(&line, 14));
+}
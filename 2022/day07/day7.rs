@@ -1,242 +1,561 @@
-use std::io;
 use std::str::FromStr;
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use itertools::Itertools;
 
-#[derive(Clone,PartialEq,Eq,Debug)]
-struct CommandLine {
-    command: String,
-    argument: String
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CommandLine {
+    pub command: String,
+    pub argument: String,
 }
 
-#[derive(Clone,PartialEq,Eq,Debug)]
-struct FileNode {
-    name: String,
-    size: usize,
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FileNode {
+    pub name: String,
+    pub size: usize,
 }
 
-#[derive(Clone,PartialEq,Eq,Debug)]
-struct DirNode {
-    name: String,
-    children: Vec<Rc<RefCell<FSLine>>>,
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DirNode {
+    pub name: String,
 }
 
-#[derive(Clone,PartialEq,Eq,Debug)]
-enum FSLine {
-    Command(CommandLine),
+// One line of `ls` output
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ListingEntry {
     File(FileNode),
     Dir(DirNode),
 }
 
+impl ListingEntry {
+    pub fn name(&self) -> &str {
+        match self {
+            ListingEntry::File(FileNode { name, .. }) | ListingEntry::Dir(DirNode { name }) => name,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FSLine {
+    Command(CommandLine),
+    Entry(ListingEntry),
+}
+
 impl FromStr for FSLine {
     type Err = ();
     fn from_str(input: &str) -> Result<FSLine, Self::Err> {
         let mut words = input.split_whitespace();
         match words.next().expect("Couldn't get first word") {
-            "$" => Ok(FSLine::Command(CommandLine{
+            "$" => Ok(FSLine::Command(CommandLine {
                 command: words.next().expect("Couldn't get command type").to_string(),
                 argument: words.next().unwrap_or("").to_string(),
             })),
-            "dir" => Ok(FSLine::Dir(DirNode {
-                name: words.next().expect("Couldn't get command type").to_string(),
-                children: Vec::<_>::new(),
-            })),
-            size => Ok(FSLine::File(FileNode{
+            "dir" => Ok(FSLine::Entry(ListingEntry::Dir(DirNode {
+                name: words.next().expect("Couldn't get dir name").to_string(),
+            }))),
+            size => Ok(FSLine::Entry(ListingEntry::File(FileNode {
                 size: size.parse::<usize>().expect("Couldn't parse size"),
-                name: words.next().expect("Couldn't get command type").to_string(),
-            })),
+                name: words.next().expect("Couldn't get file name").to_string(),
+            }))),
         }
     }
 }
 
-fn parse_lines(lines: &Vec<String>) -> Vec<FSLine> {
-    lines.iter()
-        .map(|line| FSLine::from_str(&line) )
-        .filter_map(Result::ok).collect()
-}
-
-fn build_tree(mut lines: Vec<FSLine>) -> Rc<RefCell<FSLine>> {
-    let mut iter = lines.iter_mut();
-
-    if let FSLine::Command(first_line) = iter.next().expect("Couldn't get first line") {
-        assert_eq!(first_line.command, "cd");
-        assert_eq!(first_line.argument, "/");
-    }
-    else {
-        panic!("First line should be directory");
-    }
-
-    let root_dir: Rc<RefCell<FSLine>> = Rc::new(RefCell::new(FSLine::Dir(DirNode {
-        name: "/".to_string(),
-        children: Vec::<_>::new(),
-    })));
-    let mut current_directory: Vec<Rc<RefCell<FSLine>>> = vec![root_dir.clone()];
-
-    let mut ls_mode = false;
-    while let Some(line) = iter.next() {
-        match line {
-            FSLine::Command(command) => {
-                match command.command.as_str() {
-                    "ls" => {
-                        ls_mode = true;
-                    },
-                    "cd" => {
-                        ls_mode = false;
-                        if command.argument == ".." {
-                            current_directory.pop();
-                        }
-                        else {
-                            if let FSLine::Dir(ref mut cwd_as_dir) =
-                                *(*current_directory.last()
-                                    .expect("Couldn't get current directory for cd"))
-                                    .clone()
-                                    .borrow_mut() {
-                                current_directory.push(cwd_as_dir.children.iter_mut()
-                                    .find(|child| {
-                                        if let FSLine::Dir(ref child_dir) = *(*child).borrow() {
-                                            (*child_dir).name == command.argument
-                                        }
-                                        else {
-                                            false
-                                        }
-                                    })
-                                    .expect("Couldn't find dir to cd to")
-                                    .clone());
-                            }
-                        }
-                    },
-                    _ => ()
+pub fn parse_lines(lines: &[String]) -> Vec<FSLine> {
+    lines
+        .iter()
+        .map(|line| FSLine::from_str(line))
+        .filter_map(Result::ok)
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FsError {
+    // `cd` into a file, or into a directory its parent's listing didn't have
+    NoSuchDir(String),
+    // `ls` output without an `ls` before it
+    UnexpectedOutput(String),
+    UnknownCommand(String),
+    // A directory listed twice with different contents
+    InconsistentListing(String),
+    // The same name listed twice in one directory
+    DuplicateEntry(String),
+}
+
+pub type NodeId = usize;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodeKind {
+    File { size: usize },
+    Dir { children: Vec<NodeId>, listed: bool },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+}
+
+// Every node lives in one Vec and refers to the others by index. A node is
+// always added after its parent, so a parent's id is smaller than its children's.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FileSystem {
+    pub nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: NodeKind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+            }],
+        }
+    }
+
+    // Replays a terminal session, checking every listing against what's
+    // already known about the directory
+    pub fn from_terminal(lines: &[FSLine]) -> Result<FileSystem, FsError> {
+        let mut fs = FileSystem::new();
+        let mut cwd = FileSystem::ROOT;
+        let mut listing: Option<Vec<ListingEntry>> = None;
+
+        for line in lines {
+            match line {
+                FSLine::Command(command) => {
+                    if let Some(entries) = listing.take() {
+                        fs.add_listing(cwd, entries)?;
+                    }
+                    match command.command.as_str() {
+                        "ls" => listing = Some(Vec::new()),
+                        "cd" => cwd = fs.enter(cwd, &command.argument)?,
+                        other => return Err(FsError::UnknownCommand(other.to_string())),
+                    }
                 }
-            },
-            FSLine::Dir(_) => {
-                assert_eq!(ls_mode, true);
-
-                if let FSLine::Dir(ref mut cwd_as_dir) =
-                    *(*current_directory.last()
-                        .expect("Couldn't get current directory for cd"))
-                        .clone()
-                        .borrow_mut() {
-                    cwd_as_dir.children.push(Rc::new(RefCell::new(line.to_owned())));
+                FSLine::Entry(entry) => {
+                    listing
+                        .as_mut()
+                        .ok_or_else(|| FsError::UnexpectedOutput(entry.name().to_string()))?
+                        .push(entry.clone());
                 }
-            },
-            FSLine::File(_) => {
-                assert_eq!(ls_mode, true);
-
-                if let FSLine::Dir(ref mut cwd_as_dir) =
-                    *(*current_directory.last()
-                        .expect("Couldn't get current directory for cd"))
-                        .clone()
-                        .borrow_mut() {
-                    cwd_as_dir.children.push(Rc::new(RefCell::new(line.to_owned())));
+            }
+        }
+        if let Some(entries) = listing {
+            fs.add_listing(cwd, entries)?;
+        }
+        Ok(fs)
+    }
+
+    // Like `resolve`, but a directory that hasn't been listed yet gets any
+    // missing subdirectory made on the way, as `cd` shows it's there
+    fn enter(&mut self, cwd: NodeId, path: &str) -> Result<NodeId, FsError> {
+        let start = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            cwd
+        };
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(start, |id, part| {
+                let next = match self.resolve(id, part) {
+                    Some(next) => next,
+                    None if !self.is_listed(id) => self.add_node(
+                        id,
+                        ListingEntry::Dir(DirNode {
+                            name: part.to_string(),
+                        }),
+                    ),
+                    None => return Err(FsError::NoSuchDir(self.join(id, part))),
+                };
+                if self.is_dir(next) {
+                    Ok(next)
+                } else {
+                    Err(FsError::NoSuchDir(self.path(next)))
                 }
-            },
+            })
+    }
+
+    // Anything already known about the directory, whether from an earlier
+    // listing or from `cd`, has to be in the listing. A directory that's
+    // been listed before has to be listed the same way again.
+    fn add_listing(&mut self, dir: NodeId, entries: Vec<ListingEntry>) -> Result<(), FsError> {
+        let mut names: Vec<&str> = entries.iter().map(ListingEntry::name).collect();
+        names.sort();
+        if let Some(name) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(FsError::DuplicateEntry(self.join(dir, name[0])));
+        }
+
+        let known: Vec<ListingEntry> = self
+            .children(dir)
+            .iter()
+            .map(|&id| self.entry(id))
+            .collect();
+        if known.iter().any(|entry| !entries.contains(entry))
+            || self.is_listed(dir) && known.len() != entries.len()
+        {
+            return Err(FsError::InconsistentListing(self.path(dir)));
+        }
+
+        for entry in entries {
+            if !known.contains(&entry) {
+                self.add_node(dir, entry);
+            }
+        }
+        if let NodeKind::Dir { listed, .. } = &mut self.nodes[dir].kind {
+            *listed = true;
+        }
+        Ok(())
+    }
+
+    fn add_node(&mut self, dir: NodeId, entry: ListingEntry) -> NodeId {
+        let (name, kind) = match entry {
+            ListingEntry::File(FileNode { name, size }) => (name, NodeKind::File { size }),
+            ListingEntry::Dir(DirNode { name }) => (
+                name,
+                NodeKind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+            ),
         };
-    };
-    root_dir
-}
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name,
+            parent: Some(dir),
+            kind,
+        });
+        if let NodeKind::Dir { children, .. } = &mut self.nodes[dir].kind {
+            children.push(id);
+        }
+        id
+    }
 
-// Returns (sizes of child directories, size of self)
-fn size_of_dirs(dir: &DirNode) -> (Vec<usize>, usize) {
-    let mut ret = Vec::<usize>::new();
+    // The listing line that would have produced a node
+    fn entry(&self, id: NodeId) -> ListingEntry {
+        let name = self.nodes[id].name.to_string();
+        match self.nodes[id].kind {
+            NodeKind::File { size } => ListingEntry::File(FileNode { name, size }),
+            NodeKind::Dir { .. } => ListingEntry::Dir(DirNode { name }),
+        }
+    }
 
-    let dir_size = dir.children.iter()
-        .map(|child| match &*child.borrow() {
-            FSLine::Dir(dir) => {
-                let (mut child_dir_sizes, child_size) = size_of_dirs(&dir);
-                ret.append(&mut child_dir_sizes);
-                child_size
-            },
-            FSLine::File(FileNode {name: _, size}) => *size,
-            _ => 0,
-        }).sum();
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir { .. })
+    }
 
-    ret.push(dir_size);
+    pub fn is_listed(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir { listed: true, .. })
+    }
 
-    (ret, dir_size)
-}
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            NodeKind::Dir { children, .. } => children,
+            NodeKind::File { .. } => &[],
+        }
+    }
+
+    // Follows an absolute or relative path from `cwd`, with `..` and `.`
+    // handled the way a shell would. `..` at the root stays at the root.
+    pub fn resolve(&self, cwd: NodeId, path: &str) -> Option<NodeId> {
+        let start = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            cwd
+        };
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(start, |id, part| match part {
+                "." => Some(id),
+                ".." => Some(self.nodes[id].parent.unwrap_or(FileSystem::ROOT)),
+                name => self
+                    .children(id)
+                    .iter()
+                    .copied()
+                    .find(|&child| self.nodes[child].name == name),
+            })
+    }
 
-fn size_of_dirs_with_names(dir: &DirNode, name: String) -> (HashMap::<String, usize>, usize) {
-    let dir_name = (name + "/" + &dir.name).to_string();
+    pub fn path(&self, id: NodeId) -> String {
+        match self.nodes[id].parent {
+            None => "/".to_string(),
+            Some(parent) => self.join(parent, &self.nodes[id].name),
+        }
+    }
 
-    let mut ret = HashMap::<String, usize>::new();
+    fn join(&self, dir: NodeId, name: &str) -> String {
+        let path = self.path(dir);
+        if name.starts_with('/') {
+            name.to_string()
+        } else if path == "/" {
+            format!("/{}", name)
+        } else {
+            format!("{}/{}", path, name)
+        }
+    }
 
-    let dir_size = dir.children.iter()
-        .map(|child| match &*child.borrow() {
-            FSLine::Dir(dir) => {
-                let (child_dir_sizes, child_size) = size_of_dirs_with_names(&dir, dir_name.to_string());
-                ret.extend(child_dir_sizes);
-                child_size
-            },
-            FSLine::File(FileNode {name: _, size}) => *size,
-            _ => 0,
-        }).sum();
+    // Total size of every node, indexed by id
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                NodeKind::File { size } => size,
+                NodeKind::Dir { .. } => 0,
+            })
+            .collect();
+        for id in (1..self.nodes.len()).rev() {
+            let parent = self.nodes[id].parent.expect("Only the root has no parent");
+            sizes[parent] += sizes[id];
+        }
+        sizes
+    }
 
-    ret.insert(dir_name, dir_size);
+    pub fn dir_sizes(&self) -> Vec<(NodeId, usize)> {
+        self.sizes()
+            .into_iter()
+            .enumerate()
+            .filter(|&(id, _)| self.is_dir(id))
+            .collect()
+    }
 
-    (ret, dir_size)
-}
+    pub fn total_size(&self) -> usize {
+        self.sizes()[FileSystem::ROOT]
+    }
+
+    // One `<size>\t<path>` line per directory, children before their parents
+    pub fn du(&self) -> String {
+        fn visit(fs: &FileSystem, sizes: &[usize], id: NodeId, out: &mut String) {
+            for &child in fs.children(id) {
+                if fs.is_dir(child) {
+                    visit(fs, sizes, child, out);
+                }
+            }
+            out.push_str(&format!("{}\t{}\n", sizes[id], fs.path(id)));
+        }
+        let mut out = String::new();
+        visit(self, &self.sizes(), FileSystem::ROOT, &mut out);
+        out
+    }
 
-fn print_tree(node: &Rc<RefCell<FSLine>>, depth: usize) {
-    print!("{: <1$}", "", depth * 2);
-    match &*node.borrow() {
-        FSLine::Dir(dir) => {
-            println!("- {} (dir)", dir.name);
-            dir.children.iter()
-                .for_each(|child| print_tree(&child, depth + 1))
-        },
-        FSLine::File(FileNode {name, size}) => {
-            println!("- {} (file, size={})", *name, *size);
-        },
-        _ => (),
+    // The listing in the puzzle's own tree format
+    pub fn tree(&self) -> String {
+        fn visit(fs: &FileSystem, id: NodeId, depth: usize, out: &mut String) {
+            let node = &fs.nodes[id];
+            out.push_str(&format!("{: <1$}", "", depth * 2));
+            match node.kind {
+                NodeKind::File { size } => {
+                    out.push_str(&format!("- {} (file, size={})\n", node.name, size))
+                }
+                NodeKind::Dir { .. } => {
+                    out.push_str(&format!("- {} (dir)\n", node.name));
+                    for &child in fs.children(id) {
+                        visit(fs, child, depth + 1, out);
+                    }
+                }
+            }
+        }
+        let mut out = String::new();
+        visit(self, FileSystem::ROOT, 0, &mut out);
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        fn escape(s: &str) -> String {
+            s.chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    c if c.is_control() => format!("\\u{:04x}", c as u32),
+                    c => c.to_string(),
+                })
+                .collect()
+        }
+        fn visit(fs: &FileSystem, sizes: &[usize], id: NodeId) -> String {
+            let node = &fs.nodes[id];
+            match node.kind {
+                NodeKind::File { size } => format!(
+                    r#"{{"name":"{}","type":"file","size":{}}}"#,
+                    escape(&node.name),
+                    size
+                ),
+                NodeKind::Dir { .. } => format!(
+                    r#"{{"name":"{}","type":"dir","size":{},"children":[{}]}}"#,
+                    escape(&node.name),
+                    sizes[id],
+                    fs.children(id)
+                        .iter()
+                        .map(|&child| visit(fs, sizes, child))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ),
+            }
+        }
+        visit(self, &self.sizes(), FileSystem::ROOT)
     }
 }
 
-fn part1(sizes: &Vec<usize>, max_size: usize) -> usize {
-    sizes.iter()
-        .filter(|&size| *size <= max_size)
-        .copied()
+pub fn part1(fs: &FileSystem, max_size: usize) -> usize {
+    fs.dir_sizes()
+        .into_iter()
+        .map(|(_, size)| size)
+        .filter(|&size| size <= max_size)
         .sum()
 }
 
-fn part2(sizes: &Vec<usize>, fs_size: usize, update_size: usize, fs_used: usize) -> usize {
-    let unused_space = fs_size - fs_used;
-    let space_needed = update_size - unused_space;
-    sizes.iter()
-        .filter(|&size| *size >= space_needed)
-        .copied()
+pub fn part2(fs: &FileSystem, fs_size: usize, update_size: usize) -> usize {
+    let unused_space = fs_size - fs.total_size();
+    let space_needed = update_size.saturating_sub(unused_space);
+    fs.dir_sizes()
+        .into_iter()
+        .map(|(_, size)| size)
+        .filter(|&size| size >= space_needed)
         .min()
         .expect("Couldn't find smallest dir that would free up enough space")
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines()
-                                .filter_map(Result::ok).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let parsed_lines = parse_lines(&lines);
-    let tree = build_tree(parsed_lines.to_owned());
+    fn session(text: &str) -> Result<FileSystem, FsError> {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        FileSystem::from_terminal(&parse_lines(&lines))
+    }
 
-    const DEBUG: bool = false;
+    #[test]
+    fn example() {
+        let fs = session(include_str!("example.txt")).unwrap();
+        assert_eq!(fs.total_size(), 48381165);
+        assert_eq!(part1(&fs, 100_000), 95437);
+        assert_eq!(part2(&fs, 70_000_000, 30_000_000), 24933642);
+    }
 
-    if DEBUG {
-        print_tree(&tree, 0);
+    #[test]
+    fn resolves_paths() {
+        let fs = session(include_str!("example.txt")).unwrap();
+        let resolve = |cwd: &str, path: &str| {
+            let cwd = fs.resolve(FileSystem::ROOT, cwd).unwrap();
+            fs.resolve(cwd, path).map(|id| fs.path(id))
+        };
+        assert_eq!(resolve("/", "a/e"), Some("/a/e".to_string()));
+        assert_eq!(resolve("/a/e", ".."), Some("/a".to_string()));
+        assert_eq!(resolve("/a/e", "../../d/k"), Some("/d/k".to_string()));
+        assert_eq!(resolve("/a/e", "/"), Some("/".to_string()));
+        assert_eq!(resolve("/a/e", "/d/./j"), Some("/d/j".to_string()));
+        assert_eq!(resolve("/a", "i"), None);
+        assert_eq!(resolve("/a/e/i", "x"), None);
+        // There's nothing above the root
+        assert_eq!(resolve("/", ".."), Some("/".to_string()));
+        assert_eq!(resolve("/a", "../../../a/e"), Some("/a/e".to_string()));
+        assert_eq!(resolve("/", "a//e/"), Some("/a/e".to_string()));
     }
 
-    if let FSLine::Dir(dir) = &*tree.borrow() {
-        let (sizes, total_size) = size_of_dirs(&dir);
+    #[test]
+    fn cd_follows_paths() {
+        let fs = session(
+            "$ cd /\n\
+             $ cd a/b\n\
+             $ ls\n\
+             1 x\n\
+             $ cd ../../..\n\
+             $ ls\n\
+             dir a\n\
+             $ cd /a\n\
+             $ ls\n\
+             dir b\n\
+             2 y\n\
+             $ cd /a/./b/\n\
+             $ ls\n\
+             1 x\n",
+        )
+        .unwrap();
+        assert_eq!(
+            fs.du(),
+            "1\t/a/b\n\
+             3\t/a\n\
+             3\t/\n"
+        );
+    }
 
-        println!("total size: {}", total_size);
+    #[test]
+    fn inconsistent_listings() {
+        // Listing again in a different order is fine
+        assert!(session("$ ls\ndir a\n1 b\n$ ls\n1 b\ndir a\n").is_ok());
+        assert_eq!(
+            session("$ ls\ndir a\n1 b\n$ ls\ndir a\n"),
+            Err(FsError::InconsistentListing("/".to_string()))
+        );
+        assert_eq!(
+            session("$ ls\n1 b\n$ cd /\n$ ls\n2 b\n"),
+            Err(FsError::InconsistentListing("/".to_string()))
+        );
+        assert_eq!(
+            session("$ ls\ndir a\n$ ls\ndir a\ndir c\n"),
+            Err(FsError::InconsistentListing("/".to_string()))
+        );
+        // `cd` showed there's an a/x, so a's listing has to include it
+        assert_eq!(
+            session("$ cd a/x\n$ cd ..\n$ ls\n1 y\n"),
+            Err(FsError::InconsistentListing("/a".to_string()))
+        );
+        assert_eq!(
+            session("$ ls\n1 b\ndir b\n"),
+            Err(FsError::DuplicateEntry("/b".to_string()))
+        );
+    }
 
-        if DEBUG {
-            let (sizes, _) = size_of_dirs_with_names(&dir, "".to_string());
-            println!("{} total dirs:", sizes.len());
-            for name in sizes.keys().sorted() {
-                println!("{}: {}", name, sizes[name]);
-            }
-        }
+    #[test]
+    fn other_errors() {
+        assert_eq!(
+            session("$ ls\ndir a\n$ cd b\n"),
+            Err(FsError::NoSuchDir("/b".to_string()))
+        );
+        assert_eq!(
+            session("$ ls\n1 a\n$ cd a\n"),
+            Err(FsError::NoSuchDir("/a".to_string()))
+        );
+        assert_eq!(
+            session("$ cd /\n1 a\n"),
+            Err(FsError::UnexpectedOutput("a".to_string()))
+        );
+        assert_eq!(
+            session("$ rm a\n"),
+            Err(FsError::UnknownCommand("rm".to_string()))
+        );
+    }
 
-        println!("Part 1: {}", part1(&sizes, 100_000));
-        println!("Part 2: {}", part2(&sizes, 70_000_000, 30_000_000, total_size));
-    };
+    #[test]
+    fn json() {
+        let fs = session(
+            "$ cd /\n\
+             $ ls\n\
+             dir a\n\
+             10 x\"y\\z\n\
+             $ cd a\n\
+             $ ls\n\
+             5 b\n\
+             dir c\n",
+        )
+        .unwrap();
+        assert_eq!(
+            fs.to_json(),
+            concat!(
+                r#"{"name":"/","type":"dir","size":15,"children":["#,
+                r#"{"name":"a","type":"dir","size":5,"children":["#,
+                r#"{"name":"b","type":"file","size":5},"#,
+                r#"{"name":"c","type":"dir","size":0,"children":[]}]},"#,
+                r#"{"name":"x\"y\\z","type":"file","size":10}]}"#
+            )
+        );
+    }
 }
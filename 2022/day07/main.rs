@@ -0,0 +1,27 @@
+use std::env;
+use std::io;
+
+use day7::{parse_lines, part1, part2, FileSystem};
+
+fn main() {
+    // Optionally print a `tree`, `du` or `json` report of the rebuilt filesystem
+    let report = env::args()
+        .skip(1)
+        .find(|arg| matches!(arg.as_str(), "tree" | "du" | "json"));
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let parsed_lines = parse_lines(&lines);
+    let fs = FileSystem::from_terminal(&parsed_lines).expect("Couldn't rebuild filesystem");
+
+    match report.as_deref() {
+        Some("tree") => print!("{}", fs.tree()),
+        Some("du") => print!("{}", fs.du()),
+        Some("json") => println!("{}", fs.to_json()),
+        _ => (),
+    }
+
+    println!("total size: {}", fs.total_size());
+    println!("Part 1: {}", part1(&fs, 100_000));
+    println!("Part 2: {}", part2(&fs, 70_000_000, 30_000_000));
+}
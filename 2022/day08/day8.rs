@@ -1,160 +1,186 @@
-use std::{collections::HashSet, io};
+use std::cmp::Reverse;
 
-fn parse_grid(lines: &Vec<String>) -> Vec<Vec<i32>> {
-    lines
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse::<i32>())
-                .filter_map(Result::ok)
-                .collect()
-        })
-        .collect()
+// A rectangular grid stored row by row, used for the tree heights and for
+// everything worked out about each tree
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<T>,
 }
 
-fn part1(grid: &Vec<Vec<i32>>) -> usize {
-    let mut visible_trees = HashSet::<(usize, usize)>::new();
-
-    // From top
-    for (y, row) in grid.iter().enumerate() {
-        let mut highest: i32 = -1;
-
-        // From top-left
-        for (x, &tree) in row.iter().enumerate() {
-            if tree > highest {
-                visible_trees.insert((x, y));
-                highest = tree;
-            }
+impl<T: Copy> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
         }
+    }
 
-        // From top-right
-        highest = -1;
-        for (x, &tree) in row.iter().enumerate().rev() {
-            if tree > highest {
-                visible_trees.insert((x, y));
-                highest = tree;
-            }
-        }
+    pub fn get(&self, x: usize, y: usize) -> T {
+        self.cells[y * self.width + x]
     }
 
-    // From left
-    for x in 0..grid[0].len() {
-        let mut highest: i32 = -1;
-        // From left-top
-        for (y, row) in grid.iter().enumerate() {
-            let tree: i32 = row[x];
-            if tree > highest {
-                visible_trees.insert((x, y));
-                highest = tree;
-            }
-        }
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.cells[y * self.width + x] = value;
+    }
 
-        highest = -1;
-        // From left-bottom
-        for (y, row) in grid.iter().enumerate().rev() {
-            let tree: i32 = row[x];
-            if tree > highest {
-                visible_trees.insert((x, y));
-                highest = tree;
-            }
-        }
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
     }
 
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &tree) in row.iter().enumerate() {
-            if visible_trees.contains(&(x, y)) {
-                print!("{}", tree);
-            } else {
-                print!("X");
-            }
-        }
-        println!("");
+    // Every cell's coordinates and value, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| ((i % self.width, i / self.width), value))
     }
 
-    visible_trees.len()
+    // Every row and column, both forwards and backwards, as the coordinates
+    // of the trees in the order someone looking in from that edge sees them
+    fn sight_lines(&self) -> Vec<Vec<(usize, usize)>> {
+        let rows = (0..self.height).map(|y| (0..self.width).map(|x| (x, y)).collect());
+        let columns = (0..self.width).map(|x| (0..self.height).map(|y| (x, y)).collect());
+        rows.chain(columns)
+            .flat_map(|line: Vec<_>| {
+                let reversed = line.iter().rev().copied().collect();
+                [line, reversed]
+            })
+            .collect()
+    }
 }
 
-fn calc_scenic_score(grid: &Vec<Vec<i32>>, tree_x: usize, tree_y: usize) -> usize {
-    let tree = grid[tree_y][tree_x];
-    let mut score: usize = 1;
-    // To right
-    {
-        let mut score_right = 0;
-        for x in (tree_x + 1)..grid[0].len() {
-            if grid[tree_y][x] < tree {
-                score_right += 1;
-            } else {
-                score_right += 1;
-                break;
-            }
-        }
-        //println!("Score right: {}", score_right);
-        score *= score_right;
-    }
-    // To left
-    {
-        let mut score_left = 0;
-        for x in (0..tree_x).rev() {
-            if grid[tree_y][x] < tree {
-                score_left += 1;
-            } else {
-                score_left += 1;
-                break;
-            }
-        }
-        score *= score_left;
-        //println!("Score left: {}", score_left);
+pub fn parse_grid(lines: &[String]) -> Grid<i32> {
+    let rows: Vec<Vec<i32>> = lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|c| c.to_string().parse::<i32>())
+                .filter_map(Result::ok)
+                .collect()
+        })
+        .filter(|row: &Vec<i32>| !row.is_empty())
+        .collect();
+    let width = rows.first().map_or(0, Vec::len);
+    assert!(
+        rows.iter().all(|row| row.len() == width),
+        "Rows should all be the same length"
+    );
+    Grid {
+        width,
+        height: rows.len(),
+        cells: rows.into_iter().flatten().collect(),
     }
-    // To top
-    {
-        let mut score_top = 0;
-        for y in (0..tree_y).rev() {
-            if grid[y][tree_x] < tree {
-                score_top += 1;
-            } else {
-                score_top += 1;
-                break;
+}
+
+// Which trees can be seen from outside the grid, and each tree's scenic
+// score
+pub struct Survey {
+    pub visible: Grid<bool>,
+    pub scenic_scores: Grid<usize>,
+}
+
+// Walks every sight line keeping a stack of the trees that are still in
+// view looking back towards the edge, from tallest to shortest. A tree hides
+// every shorter tree on the stack, so after popping those the top of the
+// stack is whatever blocks its view, and if the stack is empty nothing is
+// as tall, which makes it visible from the edge. Every tree is pushed and
+// popped once per line, so this is linear in the number of trees.
+pub fn survey(grid: &Grid<i32>) -> Survey {
+    let mut visible = Grid::new(grid.width, grid.height, false);
+    let mut scenic_scores = Grid::new(grid.width, grid.height, 1);
+    for line in grid.sight_lines() {
+        let mut stack: Vec<usize> = Vec::new();
+        for (i, &(x, y)) in line.iter().enumerate() {
+            let tree = grid.get(x, y);
+            while let Some(&top) = stack.last() {
+                let (top_x, top_y) = line[top];
+                if grid.get(top_x, top_y) >= tree {
+                    break;
+                }
+                stack.pop();
             }
+            let viewing_distance = match stack.last() {
+                Some(&blocker) => i - blocker,
+                None => {
+                    visible.set(x, y, true);
+                    i
+                }
+            };
+            scenic_scores.set(x, y, scenic_scores.get(x, y) * viewing_distance);
+            stack.push(i);
         }
-        score *= score_top;
-        //println!("Score top: {}", score_top);
     }
-    // To bottom
-    {
-        let mut score_bottom = 0;
-        for y in (tree_y + 1)..grid.len() {
-            if grid[y][tree_x] < tree {
-                score_bottom += 1;
-            } else {
-                score_bottom += 1;
-                break;
-            }
-        }
-        score *= score_bottom;
-        //println!("Score bottom: {}", score_bottom);
+    Survey {
+        visible,
+        scenic_scores,
     }
-    score
 }
 
-fn part2(grid: &Vec<Vec<i32>>) -> usize {
-    grid.iter()
-        .enumerate()
-        .map(|(y, row)| {
+// The tree with the highest scenic score, picking the first one going row by
+// row if there's a tie
+pub fn best_tree(survey: &Survey) -> ((usize, usize), usize) {
+    survey
+        .scenic_scores
+        .iter()
+        .min_by_key(|(_, score)| Reverse(*score))
+        .expect("Couldn't find best tree")
+}
+
+// Shades every tree by its scenic score relative to the best one
+pub fn heatmap(survey: &Survey) -> String {
+    static SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+    let (_, best) = best_tree(survey);
+    survey
+        .scenic_scores
+        .rows()
+        .map(|row| {
             row.iter()
-                .enumerate()
-                .map(|(x, tree)| calc_scenic_score(&grid, x, y))
-                .max()
-                .expect("Couldn't find max for row")
+                .map(|&score| {
+                    // Scores grow as a product of four distances, so shade
+                    // by the log to keep mid-range trees distinguishable
+                    let level = if score == 0 {
+                        0.0
+                    } else {
+                        (score as f64).ln_1p() / (best as f64).ln_1p()
+                    };
+                    SHADES[(level * (SHADES.len() - 1) as f64).round() as usize]
+                })
+                .collect::<String>()
         })
-        .max()
-        .expect("Couldn't find max for column")
+        .fold(String::new(), |a, s| a + &s + "\n")
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+// Draws the grid with every tree that can't be seen from outside crossed out
+pub fn render_visible(grid: &Grid<i32>, survey: &Survey) -> String {
+    grid.rows()
+        .zip(survey.visible.rows())
+        .map(|(heights, visible)| {
+            heights
+                .iter()
+                .zip(visible)
+                .map(|(tree, &visible)| {
+                    if visible {
+                        tree.to_string()
+                    } else {
+                        "X".to_string()
+                    }
+                })
+                .collect::<String>()
+        })
+        .fold(String::new(), |a, s| a + &s + "\n")
+}
 
-    let grid = parse_grid(&lines);
+pub fn part1(survey: &Survey) -> usize {
+    survey
+        .visible
+        .iter()
+        .filter(|(_, visible)| *visible)
+        .count()
+}
 
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
+pub fn part2(survey: &Survey) -> usize {
+    best_tree(survey).1
 }
@@ -0,0 +1,29 @@
+use std::env;
+use std::io;
+
+use day8::{best_tree, heatmap, parse_grid, part1, part2, render_visible, survey};
+
+fn main() {
+    // `heatmap` also draws which trees can be seen from outside, every
+    // tree's scenic score and where the best tree is
+    let show_heatmap = env::args().skip(1).any(|arg| arg == "heatmap");
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let grid = parse_grid(&lines);
+    let survey = survey(&grid);
+
+    if show_heatmap {
+        print!("{}", render_visible(&grid, &survey));
+        println!();
+        print!("{}", heatmap(&survey));
+        let ((x, y), score) = best_tree(&survey);
+        println!(
+            "Best tree is at ({}, {}) with a scenic score of {}",
+            x, y, score
+        );
+    }
+
+    println!("Part 1: {}", part1(&survey));
+    println!("Part 2: {}", part2(&survey));
+}
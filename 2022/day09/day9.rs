@@ -1,24 +1,22 @@
 use colored::Colorize;
 use once_cell::sync::Lazy;
-use std::cmp::{max, min};
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
 use std::thread;
 use std::time;
-use std::{collections::HashSet, io};
 
-static PRINT_GRID: bool = false;
-static ANIMATE_GRID: bool = false;
-static ANIMATION_SPEED: time::Duration = time::Duration::from_millis(20);
+pub type Pos = (i32, i32);
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum Direction {
+pub enum Direction {
     Right,
     Left,
     Up,
     Down,
 }
 
-fn parse_moves(lines: &Vec<String>) -> Vec<(Direction, usize)> {
+pub fn parse_moves(lines: &[String]) -> Vec<(Direction, usize)> {
     lines
         .iter()
         .map(|line| {
@@ -41,103 +39,7 @@ fn parse_moves(lines: &Vec<String>) -> Vec<(Direction, usize)> {
         .collect()
 }
 
-fn print_grid(
-    head_pos: (i32, i32),
-    tail_poss: &Vec<(i32, i32)>,
-    visited: &HashSet<(i32, i32)>,
-    visited_of_interest: &HashSet<(i32, i32)>,
-) {
-    static MAX_X: i32 = 31;
-    static MIN_X: i32 = -MAX_X;
-    static MAX_Y: i32 = 30;
-    static MIN_Y: i32 = -MAX_Y;
-
-    let mut covers = HashMap::<String, Vec<String>>::new();
-
-    let reduce_pos = |getter: fn(&(i32, i32)) -> i32, reduce: &fn(i32, i32) -> i32| {
-        reduce(
-            getter(&head_pos),
-            tail_poss
-                .iter()
-                .map(getter)
-                .reduce(reduce)
-                .expect("Couldn't get min tail pos"),
-        )
-    };
-
-    let min_y_pos = reduce_pos(|pos| pos.1, &(min::<i32> as fn(i32, i32) -> i32));
-    let max_y_pos = reduce_pos(|pos| pos.1, &(max::<i32> as fn(i32, i32) -> i32));
-    let min_x_pos = reduce_pos(|pos| pos.0, &(min::<i32> as fn(i32, i32) -> i32));
-    let max_x_pos = reduce_pos(|pos| pos.0, &(max::<i32> as fn(i32, i32) -> i32));
-    for y in (min(min_y_pos, max(MIN_Y, MIN_Y - (MAX_Y - max_y_pos)))
-        ..=max(max_y_pos, min(MAX_Y, MAX_Y - (MIN_Y - min_y_pos))))
-        .rev()
-    {
-        for x in min(min_x_pos, max(MIN_X, MIN_X - (MAX_X - max_x_pos)))
-            ..=max(max_x_pos, min(MAX_X, MAX_X - (MIN_X - min_x_pos)))
-        {
-            let matching_tail_poss = tail_poss
-                .iter()
-                .enumerate()
-                .filter_map(|(i, pos)| {
-                    if pos.0 == x && pos.1 == y {
-                        Some(i + 1)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
-            let num_matching_tails = matching_tail_poss.len();
-            if head_pos.0 == x && head_pos.1 == y {
-                print!("{}", format!("H").bright_green());
-                let mut covers_val: Vec<_> =
-                    matching_tail_poss.iter().map(usize::to_string).collect();
-                if x == 0 && y == 0 {
-                    covers_val.push("s".to_string());
-                }
-                covers.insert("H".to_string(), covers_val);
-            } else if num_matching_tails > 0 {
-                let mut matching_tails = matching_tail_poss.iter();
-                let tail_index = *matching_tails.next().expect("Couldn't get tail name");
-                print!("{}", format!("{}", tail_index).bright_green());
-                let mut covers_val: Vec<_> = matching_tails.map(usize::to_string).collect();
-                if x == 0 && y == 0 {
-                    covers_val.push("s".to_string());
-                }
-                covers.insert(tail_index.to_string(), covers_val);
-            } else if x == 0 && y == 0 {
-                static START_STR: Lazy<String> = Lazy::new(|| format!("s").red().to_string());
-                print!("{}", *START_STR);
-            } else {
-                static INTEREST_STR: Lazy<String> =
-                    Lazy::new(|| format!("X").bright_blue().to_string());
-                static VISITED_STR: Lazy<String> = Lazy::new(|| format!("-").green().to_string());
-                if visited_of_interest.contains(&(x, y)) {
-                    print!("{}", *INTEREST_STR);
-                } else if visited.contains(&(x, y)) {
-                    print!("{}", *VISITED_STR);
-                } else {
-                    print!(" ");
-                }
-            }
-        }
-        println!();
-    }
-    println!(
-        "{}",
-        covers
-            .iter()
-            .filter_map(|(k, v)| if v.len() > 0 {
-                Some(k.to_string() + " covers " + &v.join(", "))
-            } else {
-                None
-            })
-            .collect::<Vec<_>>()
-            .join("; ")
-    );
-}
-
-fn get_dir(dir: Direction) -> (i32, i32) {
+pub fn get_dir(dir: Direction) -> Pos {
     match dir {
         Direction::Right => (1, 0),
         Direction::Left => (-1, 0),
@@ -146,15 +48,15 @@ fn get_dir(dir: Direction) -> (i32, i32) {
     }
 }
 
-fn add(lhs: (i32, i32), rhs: (i32, i32)) -> (i32, i32) {
+pub fn add(lhs: Pos, rhs: Pos) -> Pos {
     (lhs.0 + rhs.0, lhs.1 + rhs.1)
 }
 
-fn sub(lhs: (i32, i32), rhs: (i32, i32)) -> (i32, i32) {
+pub fn sub(lhs: Pos, rhs: Pos) -> Pos {
     (lhs.0 - rhs.0, lhs.1 - rhs.1)
 }
 
-fn dir(diff: (i32, i32)) -> (i32, i32) {
+pub fn dir(diff: Pos) -> Pos {
     (
         if diff.0 != 0 {
             diff.0 / i32::abs(diff.0)
@@ -169,59 +71,180 @@ fn dir(diff: (i32, i32)) -> (i32, i32) {
     )
 }
 
-fn rope_sim(moves: &Vec<(Direction, usize)>, num_tails: usize, tail_of_interest: usize) -> usize {
-    let mut visited = HashSet::<(i32, i32)>::new();
-    let mut visited_of_interest = HashSet::<(i32, i32)>::new();
-    let mut head_pos = (0, 0);
-    let mut tail_poss = vec![head_pos; num_tails];
-    visited.insert(head_pos);
-    visited_of_interest.insert(head_pos);
+// How a knot catches up once its leader is no longer touching it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FollowRule {
+    // The puzzle's rule, which is diagonal first: a knot that isn't in line
+    // with its leader always steps diagonally towards it
+    Puzzle,
+    // Steps straight along the wider gap, only going diagonally when a
+    // straight step wouldn't be enough to touch the leader again
+    StraightFirst,
+    // Takes the leader's previous position, like a snake's body
+    Trail,
+}
 
-    if PRINT_GRID {
-        print_grid(head_pos, &tail_poss, &visited, &visited_of_interest);
+impl FollowRule {
+    pub fn follow(&self, prev_leader: Pos, leader: Pos, knot: Pos) -> Pos {
+        let diff = sub(leader, knot);
+        if i32::abs(diff.0) <= 1 && i32::abs(diff.1) <= 1 {
+            return knot;
+        }
+        let step = dir(diff);
+        match self {
+            FollowRule::Puzzle => add(knot, step),
+            FollowRule::StraightFirst => {
+                if i32::abs(diff.0) >= 2 && i32::abs(diff.1) >= 2 {
+                    add(knot, step)
+                } else if i32::abs(diff.0) >= 2 {
+                    add(knot, (step.0, 0))
+                } else {
+                    add(knot, (0, step.1))
+                }
+            }
+            FollowRule::Trail => prev_leader,
+        }
     }
+}
 
-    for (head_move, count) in moves {
-        for _ in 0..*count {
-            head_pos = add(head_pos, get_dir(*head_move));
-            visited.insert(head_pos);
+pub struct Rope {
+    // Positions of every knot after each step of the head, the head first.
+    // Step 0 is the start with all the knots on top of each other.
+    pub history: Vec<Vec<Pos>>,
+    // Every position each knot has been in
+    pub visited: Vec<HashSet<Pos>>,
+}
 
-            let mut prev_pos = head_pos;
-            for tail_pos in &mut tail_poss {
-                let diff = sub(prev_pos, *tail_pos);
+pub fn rope_sim(moves: &[(Direction, usize)], num_knots: usize, rule: FollowRule) -> Rope {
+    assert!(num_knots > 0, "A rope needs a head");
+    let mut knots = vec![(0, 0); num_knots];
+    let mut history = vec![knots.clone()];
+    let mut visited = vec![HashSet::from([(0, 0)]); num_knots];
 
-                if i32::abs(diff.0) >= 2 || i32::abs(diff.1) >= 2 {
-                    *tail_pos = add(*tail_pos, dir(diff));
-                    visited.insert(*tail_pos);
-                }
-                prev_pos = *tail_pos;
+    for (head_move, count) in moves {
+        for _ in 0..*count {
+            let mut prev_leader = knots[0];
+            knots[0] = add(knots[0], get_dir(*head_move));
+            for i in 1..num_knots {
+                let prev_knot = knots[i];
+                knots[i] = rule.follow(prev_leader, knots[i - 1], knots[i]);
+                prev_leader = prev_knot;
             }
-
-            visited_of_interest.insert(tail_poss[tail_of_interest]);
-
-            if ANIMATE_GRID {
-                thread::sleep(ANIMATION_SPEED);
-                println!("\x1Bc");
-                print_grid(head_pos, &tail_poss, &visited, &visited_of_interest);
+            for (knot, knot_visited) in knots.iter().zip(visited.iter_mut()) {
+                knot_visited.insert(*knot);
             }
-        }
-        if PRINT_GRID && !ANIMATE_GRID {
-            print_grid(head_pos, &tail_poss, &visited, &visited_of_interest);
+            history.push(knots.clone());
         }
     }
 
-    if PRINT_GRID {
-        print_grid(head_pos, &tail_poss, &visited, &visited_of_interest);
+    Rope { history, visited }
+}
+
+impl Rope {
+    pub fn num_steps(&self) -> usize {
+        self.history.len() - 1
     }
 
-    visited_of_interest.len()
-}
+    // Smallest and largest corners of the box around every knot during the
+    // steps
+    pub fn bounds(&self, steps: Range<usize>) -> (Pos, Pos) {
+        self.history[steps]
+            .iter()
+            .flatten()
+            .fold(None, |bounds: Option<(Pos, Pos)>, pos| {
+                Some(bounds.map_or((*pos, *pos), |(min, max)| {
+                    (
+                        (min.0.min(pos.0), min.1.min(pos.1)),
+                        (max.0.max(pos.0), max.1.max(pos.1)),
+                    )
+                }))
+            })
+            .expect("Need at least one step to get bounds")
+    }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+    // Draws the knots as they are after the last of the steps, along with
+    // where any knot went during them, highlighting the knot of interest
+    pub fn print_grid(&self, steps: Range<usize>, knot_of_interest: usize) {
+        self.draw(steps.clone(), knot_of_interest, self.bounds(steps));
+    }
 
-    let moves = parse_moves(&lines);
+    fn draw(&self, steps: Range<usize>, knot_of_interest: usize, bounds: (Pos, Pos)) {
+        let ((min_x, min_y), (max_x, max_y)) = bounds;
+        let knots = &self.history[steps.end - 1];
+        let visited: HashSet<Pos> = self.history[steps.clone()]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        let visited_of_interest: HashSet<Pos> = self.history[steps]
+            .iter()
+            .map(|knots| knots[knot_of_interest])
+            .collect();
+
+        let mut covers = HashMap::<String, Vec<String>>::new();
+        for y in (min_y..=max_y).rev() {
+            for x in min_x..=max_x {
+                let mut matching_knots = knots.iter().enumerate().filter_map(|(i, pos)| {
+                    if *pos == (x, y) {
+                        Some(i)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(knot) = matching_knots.next() {
+                    let name = if knot == 0 {
+                        "H".to_string()
+                    } else {
+                        knot.to_string()
+                    };
+                    print!("{}", name.bright_green());
+                    let mut covers_val: Vec<_> = matching_knots.map(|i| i.to_string()).collect();
+                    if x == 0 && y == 0 {
+                        covers_val.push("s".to_string());
+                    }
+                    covers.insert(name, covers_val);
+                } else if x == 0 && y == 0 {
+                    static START_STR: Lazy<String> =
+                        Lazy::new(|| "s".to_string().red().to_string());
+                    print!("{}", *START_STR);
+                } else {
+                    static INTEREST_STR: Lazy<String> =
+                        Lazy::new(|| "X".to_string().bright_blue().to_string());
+                    static VISITED_STR: Lazy<String> =
+                        Lazy::new(|| "-".to_string().green().to_string());
+                    if visited_of_interest.contains(&(x, y)) {
+                        print!("{}", *INTEREST_STR);
+                    } else if visited.contains(&(x, y)) {
+                        print!("{}", *VISITED_STR);
+                    } else {
+                        print!(" ");
+                    }
+                }
+            }
+            println!();
+        }
+        println!(
+            "{}",
+            covers
+                .iter()
+                .filter_map(|(k, v)| if !v.is_empty() {
+                    Some(k.to_string() + " covers " + &v.join(", "))
+                } else {
+                    None
+                })
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+    }
 
-    println!("Part 1: {}", rope_sim(&moves, 1, 0));
-    println!("Part 2: {}", rope_sim(&moves, 9, 8));
+    // Redraws the grid for every step, keeping the bounds of the whole range
+    // so the picture doesn't jump around
+    pub fn animate(&self, steps: Range<usize>, knot_of_interest: usize, delay: time::Duration) {
+        let bounds = self.bounds(steps.clone());
+        for end in steps.start + 1..=steps.end {
+            thread::sleep(delay);
+            println!("\x1Bc");
+            self.draw(steps.start..end, knot_of_interest, bounds);
+        }
+    }
 }
@@ -0,0 +1,62 @@
+use std::env;
+use std::io;
+use std::time;
+
+use day9::{parse_moves, rope_sim, FollowRule};
+
+static ANIMATION_SPEED: time::Duration = time::Duration::from_millis(20);
+
+fn main() {
+    // Optional `follow=puzzle|straight|trail` for other rope physics, and
+    // `grid[=FROM..TO]` or `animate[=FROM..TO]` to draw part 2's rope over
+    // a range of steps. Other arguments are ignored.
+    let mut rule = FollowRule::Puzzle;
+    let mut grid = None;
+    let mut animate = false;
+    for arg in env::args().skip(1) {
+        let (name, value) = arg.split_once('=').unwrap_or((&arg, ""));
+        match name {
+            "follow" => {
+                rule = match value {
+                    "puzzle" => FollowRule::Puzzle,
+                    "straight" => FollowRule::StraightFirst,
+                    "trail" => FollowRule::Trail,
+                    _ => panic!("Unknown follow rule {}", value),
+                }
+            }
+            "grid" | "animate" => {
+                animate = name == "animate";
+                grid = Some(value.split_once("..").map(|(from, to)| {
+                    (
+                        from.parse::<usize>().expect("Couldn't parse first step"),
+                        to.parse::<usize>().expect("Couldn't parse last step"),
+                    )
+                }));
+            }
+            _ => (),
+        }
+    }
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let moves = parse_moves(&lines);
+
+    let short_rope = rope_sim(&moves, 2, rule);
+    let long_rope = rope_sim(&moves, 10, rule);
+
+    if let Some(range) = grid {
+        let (from, to) = range.unwrap_or((0, long_rope.num_steps()));
+        assert!(
+            from <= to && to <= long_rope.num_steps(),
+            "Steps out of range"
+        );
+        if animate {
+            long_rope.animate(from..to + 1, 9, ANIMATION_SPEED);
+        } else {
+            long_rope.print_grid(from..to + 1, 9);
+        }
+    }
+
+    println!("Part 1: {}", short_rope.visited[1].len());
+    println!("Part 2: {}", long_rope.visited[9].len());
+}
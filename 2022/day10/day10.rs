@@ -1,79 +1,425 @@
-use std::io;
-
-enum Instruction {
-    Add { count: i32 },
-    Noop,
-}
-
-fn parse_commands(lines: &Vec<String>) -> Vec<Instruction> {
-    lines
-        .iter()
-        .filter_map(|line| {
-            let mut words = line.split_whitespace();
-            match words.next().expect("Couldn't get first word") {
-                "addx" => Some(Instruction::Add {
-                    count: words
-                        .next()
-                        .expect("Couldn't get argument")
-                        .parse::<i32>()
-                        .expect("Couldn't parse argument"),
-                }),
-                "noop" => Some(Instruction::Noop),
-                _ => None,
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// How an instruction behaves: how many cycles it takes, how many arguments
+// it takes and what it does to the registers once its last cycle is done
+#[derive(Copy, Clone, Debug)]
+pub struct Opcode {
+    pub cycles: usize,
+    pub num_args: usize,
+    pub execute: fn(&mut [i32], &[i32]),
+}
+
+// The registers and the table of opcodes a program can use
+#[derive(Clone, Debug)]
+pub struct InstructionSet {
+    // Register names and their starting values
+    pub registers: Vec<(String, i32)>,
+    opcodes: HashMap<String, Opcode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Instruction {
+    pub name: String,
+    pub opcode: Opcode,
+    pub args: Vec<i32>,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    // Counting from 1, like an editor would
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} on line {}: {:?}", self.message, self.line, self.text)
+    }
+}
+
+impl InstructionSet {
+    pub fn new(registers: &[(&str, i32)]) -> InstructionSet {
+        InstructionSet {
+            registers: registers
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+            opcodes: HashMap::new(),
+        }
+    }
+
+    // The handheld's CPU, with just an X register and `noop`/`addx`
+    pub fn standard() -> InstructionSet {
+        let mut set = InstructionSet::new(&[("x", 1)]);
+        set.add_opcode(
+            "noop",
+            Opcode {
+                cycles: 1,
+                num_args: 0,
+                execute: |_, _| (),
+            },
+        );
+        set.add_opcode(
+            "addx",
+            Opcode {
+                cycles: 2,
+                num_args: 1,
+                execute: |registers, args| registers[0] += args[0],
+            },
+        );
+        set
+    }
+
+    pub fn add_opcode(&mut self, name: &str, opcode: Opcode) {
+        // Instructions only finish at the end of a cycle, so one that takes
+        // none would never run
+        assert!(
+            opcode.cycles > 0,
+            "Opcode {} has to take at least one cycle",
+            name
+        );
+        self.opcodes.insert(name.to_string(), opcode);
+    }
+
+    pub fn register(&self, name: &str) -> Option<usize> {
+        self.registers.iter().position(|(other, _)| other == name)
+    }
+
+    pub fn parse(&self, lines: &[String]) -> Result<Vec<Instruction>, ParseError> {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let error = |message: String| ParseError {
+                    line: i + 1,
+                    text: line.to_string(),
+                    message,
+                };
+                let mut words = line.split_whitespace();
+                let name = words.next().expect("Line isn't empty");
+                let opcode = *self
+                    .opcodes
+                    .get(name)
+                    .ok_or_else(|| error(format!("Unknown instruction {}", name)))?;
+                let args = words
+                    .map(|word| {
+                        word.parse::<i32>()
+                            .map_err(|_| error(format!("Couldn't parse argument {}", word)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if args.len() != opcode.num_args {
+                    return Err(error(format!(
+                        "{} takes {} arguments but got {}",
+                        name,
+                        opcode.num_args,
+                        args.len()
+                    )));
+                }
+                Ok(Instruction {
+                    name: name.to_string(),
+                    opcode,
+                    args,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Breakpoint {
+    // Stops before the cycle starts
+    Cycle(usize),
+    // Stops before the instruction at this index starts
+    Instruction(usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Breakpoint),
+}
+
+pub struct Cpu<'a> {
+    set: &'a InstructionSet,
+    program: &'a [Instruction],
+    pub registers: Vec<i32>,
+    // Cycles completed so far
+    pub cycle: usize,
+    // Index of the current instruction, and how many of its cycles are done
+    pub pc: usize,
+    progress: usize,
+    pub breakpoints: HashSet<Breakpoint>,
+    // Prints every cycle to stderr
+    pub trace: bool,
+    // Where the last run stopped, so resuming doesn't stop there again
+    stopped_at: Option<usize>,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn new(set: &'a InstructionSet, program: &'a [Instruction]) -> Cpu<'a> {
+        Cpu {
+            set,
+            program,
+            registers: set.registers.iter().map(|(_, value)| *value).collect(),
+            cycle: 0,
+            pc: 0,
+            progress: 0,
+            breakpoints: HashSet::new(),
+            trace: false,
+            stopped_at: None,
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    pub fn register(&self, name: &str) -> i32 {
+        let index = self
+            .set
+            .register(name)
+            .unwrap_or_else(|| panic!("No register {}", name));
+        self.registers[index]
+    }
+
+    // Runs a single cycle, calling the hook with the cycle's number and the
+    // registers as they are during it. Returns false once halted.
+    pub fn step<F: FnMut(usize, &[i32])>(&mut self, hook: &mut F) -> bool {
+        if self.is_halted() {
+            return false;
+        }
+        let instruction = &self.program[self.pc];
+        self.cycle += 1;
+        self.progress += 1;
+        hook(self.cycle, &self.registers);
+        if self.trace {
+            eprintln!(
+                "Cycle {:>4} | {:>3}: {:<10} ({}/{}) | {}",
+                self.cycle,
+                self.pc,
+                instruction.to_string(),
+                self.progress,
+                instruction.opcode.cycles,
+                self.set
+                    .registers
+                    .iter()
+                    .zip(&self.registers)
+                    .map(|((name, _), value)| format!("{}={}", name, value))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+        if self.progress == instruction.opcode.cycles {
+            (instruction.opcode.execute)(&mut self.registers, &instruction.args);
+            self.pc += 1;
+            self.progress = 0;
+        }
+        true
+    }
+
+    fn breakpoint(&self) -> Option<Breakpoint> {
+        let next_cycle = Breakpoint::Cycle(self.cycle + 1);
+        let next_instruction = Breakpoint::Instruction(self.pc);
+        if self.breakpoints.contains(&next_cycle) {
+            Some(next_cycle)
+        } else if self.progress == 0 && self.breakpoints.contains(&next_instruction) {
+            Some(next_instruction)
+        } else {
+            None
+        }
+    }
+
+    // Steps until the program halts or reaches a breakpoint
+    pub fn run<F: FnMut(usize, &[i32])>(&mut self, mut hook: F) -> Stop {
+        loop {
+            if self.stopped_at != Some(self.cycle) {
+                if let Some(breakpoint) = self.breakpoint() {
+                    self.stopped_at = Some(self.cycle);
+                    return Stop::Breakpoint(breakpoint);
+                }
             }
-        })
-        .collect()
-}
-
-fn run_program(commands: &Vec<Instruction>) -> Vec<i32> {
-    commands
-        .iter()
-        .fold(vec![1, 1], |x_over_t, command| -> Vec<i32> {
-            let x = *x_over_t.last().expect("Couldn't get current x val");
-            match command {
-                Instruction::Add { count } => x_over_t.into_iter().chain([x, x + count]).collect(),
-                Instruction::Noop => x_over_t.into_iter().chain([x]).collect(),
+            if !self.step(&mut hook) {
+                return Stop::Halted;
             }
-        })
+        }
+    }
+}
+
+// A monochrome screen that's drawn one pixel per cycle, left to right and
+// top to bottom
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crt {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<bool>,
 }
 
-fn part1(registers_over_t: &Vec<i32>) -> i32 {
-    [20, 60, 100, 140, 180, 220]
-        .into_iter()
-        .map(|ind| registers_over_t[ind] * ind as i32)
-        .sum()
+impl Crt {
+    pub fn new(width: usize, height: usize) -> Crt {
+        Crt {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    // Lights the cycle's pixel if the three pixel wide sprite covers it
+    pub fn draw(&mut self, cycle: usize, sprite_x: i32) {
+        let index = (cycle - 1) % self.pixels.len();
+        let x = (index % self.width) as i32;
+        self.pixels[index] = (sprite_x - 1..=sprite_x + 1).contains(&x);
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.pixels.chunks(self.width) {
+            let line: String = row.iter().map(|&lit| if lit { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn part1(set: &InstructionSet, program: &[Instruction]) -> i32 {
+    let mut cpu = Cpu::new(set, program);
+    cpu.breakpoints
+        .extend([20, 60, 100, 140, 180, 220].map(Breakpoint::Cycle));
+    let mut total = 0;
+    while let Stop::Breakpoint(Breakpoint::Cycle(cycle)) = cpu.run(|_, _| ()) {
+        // Registers don't change until a cycle is over, so these are the
+        // values during the cycle
+        total += cpu.register("x") * cycle as i32;
+    }
+    total
 }
 
-fn part2(registers_over_t: &Vec<i32>) {
+pub fn part2(set: &InstructionSet, program: &[Instruction]) -> Crt {
     static HEIGHT: usize = 6;
     static WIDTH: usize = 40;
 
-    println!(
-        "{}",
-        (0..HEIGHT)
-            .map(|y| (0..WIDTH)
-                .map(|x| {
-                    let cycle_index = y * WIDTH + x + 1;
-                    let x_min: i32 = x as i32 - 1;
-                    let x_max: i32 = x as i32 + 1;
-                    if (x_min..=x_max).contains(&registers_over_t[cycle_index]) {
-                        '#'
-                    } else {
-                        '.'
-                    }
-                })
-                .collect::<String>())
-            .fold(String::new(), |a, s| a + &s + "\n")
-    );
+    let mut crt = Crt::new(WIDTH, HEIGHT);
+    let x = set
+        .register("x")
+        .expect("Need an X register for the sprite");
+    Cpu::new(set, program).run(|cycle, registers| crt.draw(cycle, registers[x]));
+    crt
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_text(set: &InstructionSet, text: &str) -> Result<Vec<Instruction>, ParseError> {
+        set.parse(&text.lines().map(str::to_string).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn example() {
+        let set = InstructionSet::standard();
+        let program = parse_text(&set, include_str!("example.txt")).unwrap();
+        assert_eq!(part1(&set, &program), 13140);
+        assert_eq!(
+            part2(&set, &program).to_string(),
+            "##..##..##..##..##..##..##..##..##..##..\n\
+             ###...###...###...###...###...###...###.\n\
+             ####....####....####....####....####....\n\
+             #####.....#####.....#####.....#####.....\n\
+             ######......######......######......####\n\
+             #######.......#######.......#######.....\n"
+        );
+    }
+
+    #[test]
+    fn breakpoints() {
+        let set = InstructionSet::standard();
+        let program = parse_text(&set, include_str!("small_example.txt")).unwrap();
+        let mut cpu = Cpu::new(&set, &program);
+        cpu.breakpoints.extend([
+            Breakpoint::Instruction(1),
+            Breakpoint::Cycle(3),
+            // Both stop before cycle 4, which is only reported once
+            Breakpoint::Cycle(4),
+            Breakpoint::Instruction(2),
+        ]);
+        let mut cycles = Vec::new();
+        let mut stops = Vec::new();
+        loop {
+            let stop = cpu.run(|cycle, registers| cycles.push((cycle, registers[0])));
+            stops.push((stop, cpu.cycle, cpu.pc, cpu.register("x")));
+            if stop == Stop::Halted {
+                break;
+            }
+        }
+        assert_eq!(
+            stops,
+            [
+                (Stop::Breakpoint(Breakpoint::Instruction(1)), 1, 1, 1),
+                // In the middle of addx 3
+                (Stop::Breakpoint(Breakpoint::Cycle(3)), 2, 1, 1),
+                (Stop::Breakpoint(Breakpoint::Cycle(4)), 3, 2, 4),
+                (Stop::Halted, 5, 3, -1),
+            ]
+        );
+        // Stopping doesn't skip or repeat any cycles
+        assert_eq!(cycles, [(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.run(|_, _| ()), Stop::Halted);
+    }
 
-    let commands = parse_commands(&lines);
-    let registers_over_t = run_program(&commands);
+    #[test]
+    fn parse_errors_name_the_line() {
+        let set = InstructionSet::standard();
+        let error = |line: usize, text: &str, message: &str| ParseError {
+            line,
+            text: text.to_string(),
+            message: message.to_string(),
+        };
+        assert_eq!(
+            parse_text(&set, "noop\n\naddx 1\nmul 2\n").unwrap_err(),
+            error(4, "mul 2", "Unknown instruction mul")
+        );
+        assert_eq!(
+            parse_text(&set, "addx five").unwrap_err(),
+            error(1, "addx five", "Couldn't parse argument five")
+        );
+        assert_eq!(
+            parse_text(&set, "noop\naddx 1\n  addx  ").unwrap_err(),
+            error(3, "  addx  ", "addx takes 1 arguments but got 0")
+        );
+        assert_eq!(
+            parse_text(&set, "noop 3").unwrap_err().to_string(),
+            "noop takes 0 arguments but got 1 on line 1: \"noop 3\""
+        );
+    }
 
-    println!("Part 1: {:?}", part1(&registers_over_t));
-    println!("Part 2:");
-    part2(&registers_over_t);
+    #[test]
+    #[should_panic(expected = "at least one cycle")]
+    fn opcodes_take_cycles() {
+        InstructionSet::standard().add_opcode(
+            "nothing",
+            Opcode {
+                cycles: 0,
+                num_args: 0,
+                execute: |_, _| (),
+            },
+        );
+    }
 }
@@ -0,0 +1,26 @@
+use std::env;
+use std::io;
+
+use day10::{part1, part2, Cpu, InstructionSet};
+
+fn main() {
+    // `trace` prints every cycle of the program to stderr before solving
+    let trace = env::args().skip(1).any(|arg| arg == "trace");
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let set = InstructionSet::standard();
+    let program = set
+        .parse(&lines)
+        .unwrap_or_else(|err| panic!("Couldn't parse program: {}", err));
+
+    if trace {
+        let mut cpu = Cpu::new(&set, &program);
+        cpu.trace = true;
+        cpu.run(|_, _| ());
+    }
+
+    println!("Part 1: {:?}", part1(&set, &program));
+    println!("Part 2:");
+    println!("{}", part2(&set, &program));
+}
@@ -1,97 +1,251 @@
+use std::fmt;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::io;
+
+// The right hand side of `new = ...`, made of `old`, constants, the four
+// arithmetic operators and parentheses
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Operation {
+    Old,
+    Const(u64),
+    Add(Box<Operation>, Box<Operation>),
+    Subtract(Box<Operation>, Box<Operation>),
+    Divide(Box<Operation>, Box<Operation>),
+    Multiply(Box<Operation>, Box<Operation>),
+}
+
+impl Operation {
+    pub fn parse(expr: &str) -> Operation {
+        let tokens: Vec<String> = expr
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let mut pos = 0;
+        let op = Operation::parse_sum(&tokens, &mut pos);
+        assert_eq!(pos, tokens.len(), "Trailing tokens in operation {}", expr);
+        op
+    }
+
+    fn parse_sum(tokens: &[String], pos: &mut usize) -> Operation {
+        let mut lhs = Operation::parse_product(tokens, pos);
+        while let Some(token @ ("+" | "-")) = tokens.get(*pos).map(String::as_str) {
+            *pos += 1;
+            let rhs = Box::new(Operation::parse_product(tokens, pos));
+            lhs = match token {
+                "+" => Operation::Add(Box::new(lhs), rhs),
+                _ => Operation::Subtract(Box::new(lhs), rhs),
+            };
+        }
+        lhs
+    }
+
+    fn parse_product(tokens: &[String], pos: &mut usize) -> Operation {
+        let mut lhs = Operation::parse_term(tokens, pos);
+        while let Some(token @ ("*" | "/")) = tokens.get(*pos).map(String::as_str) {
+            *pos += 1;
+            let rhs = Box::new(Operation::parse_term(tokens, pos));
+            lhs = match token {
+                "*" => Operation::Multiply(Box::new(lhs), rhs),
+                _ => Operation::Divide(Box::new(lhs), rhs),
+            };
+        }
+        lhs
+    }
+
+    fn parse_term(tokens: &[String], pos: &mut usize) -> Operation {
+        let token = tokens.get(*pos).expect("Operation ended early");
+        *pos += 1;
+        match token.as_str() {
+            "old" => Operation::Old,
+            "(" => {
+                let inner = Operation::parse_sum(tokens, pos);
+                assert_eq!(
+                    tokens.get(*pos).map(String::as_str),
+                    Some(")"),
+                    "Unclosed parenthesis in operation"
+                );
+                *pos += 1;
+                inner
+            }
+            num => Operation::Const(
+                num.parse()
+                    .unwrap_or_else(|_| panic!("Couldn't parse operation term {}", num)),
+            ),
+        }
+    }
+
+    pub fn eval(&self, old: u64) -> Result<u64, EvalError> {
+        match self {
+            Operation::Old => Ok(old),
+            Operation::Const(value) => Ok(*value),
+            Operation::Add(lhs, rhs) => lhs
+                .eval(old)?
+                .checked_add(rhs.eval(old)?)
+                .ok_or(EvalError::Overflow),
+            Operation::Subtract(lhs, rhs) => lhs
+                .eval(old)?
+                .checked_sub(rhs.eval(old)?)
+                .ok_or(EvalError::Negative),
+            Operation::Divide(lhs, rhs) => lhs
+                .eval(old)?
+                .checked_div(rhs.eval(old)?)
+                .ok_or(EvalError::DivideByZero),
+            Operation::Multiply(lhs, rhs) => lhs
+                .eval(old)?
+                .checked_mul(rhs.eval(old)?)
+                .ok_or(EvalError::Overflow),
+        }
+    }
+
+    // Whether the result modulo m only depends on old modulo m. That holds
+    // for sums and products but not for division, and subtraction can go
+    // negative, so only then can worry levels be reduced between rounds.
+    pub fn preserves_residues(&self) -> bool {
+        match self {
+            Operation::Old | Operation::Const(_) => true,
+            Operation::Add(lhs, rhs) | Operation::Multiply(lhs, rhs) => {
+                lhs.preserves_residues() && rhs.preserves_residues()
+            }
+            Operation::Subtract(..) | Operation::Divide(..) => false,
+        }
+    }
+}
+
+// Why an operation couldn't give a new worry level
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum EvalError {
+    // Doesn't fit in a u64
+    Overflow,
+    // Worry levels can't go below zero
+    Negative,
+    DivideByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Overflow => write!(f, "Overflow"),
+            EvalError::Negative => write!(f, "Negative worry level"),
+            EvalError::DivideByZero => write!(f, "Division by zero"),
+        }
+    }
+}
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-enum Operation {
-    Add { value: Option<u64> },
-    Subtract { value: Option<u64> },
-    Divide { value: Option<u64> },
-    Multiply { value: Option<u64> },
+pub enum WorryError {
+    // A monkey's operation failed while inspecting an item
+    Eval {
+        round: usize,
+        monkey: usize,
+        item: usize,
+        old: u64,
+        error: EvalError,
+    },
+    // Without relief worry levels are reduced modulo mod_val, which gives
+    // wrong answers for an operation using - or /
+    ResiduesNotKept {
+        monkey: usize,
+    },
+}
+
+impl fmt::Display for WorryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorryError::Eval {
+                round,
+                monkey,
+                item,
+                old,
+                error,
+            } => write!(
+                f,
+                "{} in round {} when monkey {} inspected item {} with worry {}",
+                error, round, monkey, item, old
+            ),
+            WorryError::ResiduesNotKept { monkey } => write!(
+                f,
+                "Monkey {}'s operation uses - or /, so worry levels can't be kept small \
+                 by reducing them modulo the product of the tests",
+                monkey
+            ),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Item {
+    // Position of the item in the starting lists, counting across monkeys
+    pub id: usize,
+    pub worry: u64,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
-struct Monkey {
-    items: Vec<u64>,
-    op: Operation,
-    test: u64,
-    test_result: (usize, usize),
+pub struct Monkey {
+    pub items: Vec<Item>,
+    pub op: Operation,
+    pub test: u64,
+    pub test_result: (usize, usize),
 }
 
-fn parse_monkeys(lines: &Vec<String>) -> Vec<Monkey> {
+pub fn parse_monkeys(lines: &[String]) -> Vec<Monkey> {
+    static OP_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Operation: new = (.+)").unwrap());
+    static TEST_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Test: divisible by (\d+)").unwrap());
+    static TEST_RESULT_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"If (true|false): throw to monkey (\d+)").unwrap());
+
+    let mut next_id = 0;
     lines
-        .chunks(7)
+        .split(|line| line.trim().is_empty())
+        .filter(|monkey_lines| !monkey_lines.is_empty())
         .map(|monkey_lines| -> Monkey {
-            let mut it = monkey_lines.iter();
-            // Skip first monkey line
-            it.next();
-            let starting_str = it.next().expect("Couldn't get starting items");
-            let starting_strs = starting_str
-                .split(":")
-                .skip(1)
-                .next()
+            let find_line = |prefix: &str| {
+                monkey_lines
+                    .iter()
+                    .map(|line| line.trim())
+                    .find(|line| line.starts_with(prefix))
+                    .unwrap_or_else(|| panic!("Couldn't find {} line", prefix))
+            };
+
+            let items: Vec<Item> = find_line("Starting items:")
+                .split(':')
+                .nth(1)
                 .expect("Couldn't get starting item list")
-                .trim()
-                .split(", ");
-            let items: Vec<u64> = starting_strs
-                .map(str::parse::<u64>)
-                .filter_map(Result::ok)
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| {
+                    next_id += 1;
+                    Item {
+                        id: next_id - 1,
+                        worry: item.parse().expect("Couldn't parse starting item"),
+                    }
+                })
                 .collect();
-            static OP_RE: Lazy<Regex> =
-                Lazy::new(|| Regex::new(r"Operation: new = old (\+|-|\*|/) (\d+|old)").unwrap());
-            let op_str = it.next().expect("Couldn't get operation").trim();
-            let parsed_op = OP_RE
-                .captures_iter(op_str)
-                .next()
-                .expect("Couldn't parse operation regex");
-            let op_val = parsed_op[2].parse::<u64>().ok();
-            let op: Operation = match &parsed_op[1] {
-                "+" => Some(Operation::Add { value: op_val }),
-                "*" => Some(Operation::Multiply { value: op_val }),
-                "/" => Some(Operation::Divide { value: op_val }),
-                "-" => Some(Operation::Subtract { value: op_val }),
-                _ => None,
-            }
-            .expect("Couldn't parse operation type");
 
-            static TEST_RE: Lazy<Regex> =
-                Lazy::new(|| Regex::new(r"Test: divisible by (\d+)").unwrap());
-            let test_str = it.next().expect("Couldn't get test").trim();
-            let parsed_test = TEST_RE
-                .captures_iter(test_str)
-                .next()
-                .expect("Couldn't parse test regex");
+            let op = Operation::parse(
+                &OP_RE
+                    .captures(find_line("Operation:"))
+                    .expect("Couldn't parse operation regex")[1],
+            );
 
-            let test: u64 = parsed_test[1]
+            let test: u64 = TEST_RE
+                .captures(find_line("Test:"))
+                .expect("Couldn't parse test regex")[1]
                 .parse::<u64>()
                 .expect("Couldn't parse test num");
 
-            static TEST_RESULT_RE: Lazy<Regex> =
-                Lazy::new(|| Regex::new(r"If (true|false): throw to monkey (\d+)").unwrap());
-
-            let result_strs = (
-                it.next().expect("Couldn't get result 1").trim(),
-                it.next().expect("Couldn't get result 2").trim(),
-            );
-            let parsed_results = (
-                TEST_RESULT_RE
-                    .captures_iter(result_strs.0)
-                    .next()
-                    .expect("Couldn't parse result regex 1"),
+            let throw_to = |prefix: &str| -> usize {
                 TEST_RESULT_RE
-                    .captures_iter(result_strs.1)
-                    .next()
-                    .expect("Couldn't parse result regex 2"),
-            );
-
-            let test_result: (usize, usize) = (
-                parsed_results.0[2]
-                    .parse::<usize>()
-                    .expect("Couldn't parse result num"),
-                parsed_results.1[2]
+                    .captures(find_line(prefix))
+                    .expect("Couldn't parse result regex")[2]
                     .parse::<usize>()
-                    .expect("Couldn't parse result num"),
-            );
+                    .expect("Couldn't parse result num")
+            };
+            let test_result = (throw_to("If true:"), throw_to("If false:"));
+
             Monkey {
                 items,
                 op,
@@ -102,91 +256,350 @@ fn parse_monkeys(lines: &Vec<String>) -> Vec<Monkey> {
         .collect()
 }
 
-fn do_round(monkeys: &mut Vec<Monkey>, worry_reduction: u64, monkey_activity: &mut Vec<usize>) {
-    let mod_val = monkeys.iter().fold(1 as u64, |v, monkey| v * monkey.test);
-    for i in 0..monkeys.len() {
-        let monkey: &mut Monkey = monkeys.get_mut(i).expect("Couldn't get monkey");
-        let destination_monkeys = monkey
-            .items
-            .drain(0..monkey.items.len())
-            .map(|mut item| {
-                match monkey.op {
-                    Operation::Add { value } => item += value.unwrap_or(item),
-                    Operation::Subtract { value } => item -= value.unwrap_or(item),
-                    Operation::Divide { value } => item /= value.unwrap_or(item),
-                    Operation::Multiply { value } => item *= value.unwrap_or(item),
-                }
-                item /= worry_reduction;
-                if item % monkey.test == 0 {
-                    (monkey.test_result.0, item % mod_val)
-                } else {
-                    (monkey.test_result.1, item % mod_val)
-                }
-            })
-            .collect::<Vec<_>>();
-
-        *monkey_activity
-            .get_mut(i)
-            .expect("Couldn't update monkey activity") += destination_monkeys.len();
-
-        //println!("{:?}", destination_monkeys);
-        destination_monkeys
-            .into_iter()
-            .for_each(|(destination_monkey, item)| {
-                monkeys
-                    .get_mut(destination_monkey)
+// One item being inspected and thrown
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Throw {
+    pub round: usize,
+    pub from: usize,
+    pub to: usize,
+    pub item: usize,
+    // Worry after the operation and relief, before reducing it by mod_val
+    pub worry: u64,
+    // What's passed on instead. Without relief this is worry modulo mod_val,
+    // which every test divides, so later tests see the same results. Relief
+    // divides before the test and doesn't keep residues, so then worry is
+    // passed on unchanged.
+    pub reduced: u64,
+    pub test: u64,
+    pub divisible: bool,
+}
+
+pub struct Simulation {
+    pub monkeys: Vec<Monkey>,
+    pub worry_reduction: u64,
+    // Product of every test, without relief the worry levels only matter
+    // modulo this
+    pub mod_val: u64,
+    pub monkey_activity: Vec<usize>,
+    pub round: usize,
+    // Every throw so far, only kept when tracing
+    pub trace: Option<Vec<Throw>>,
+    starting_monkey: Vec<usize>,
+}
+
+impl Simulation {
+    pub fn new(monkeys: Vec<Monkey>, worry_reduction: u64) -> Simulation {
+        let mut starting_monkey = vec![0; monkeys.iter().map(|monkey| monkey.items.len()).sum()];
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for item in &monkey.items {
+                starting_monkey[item.id] = i;
+            }
+        }
+        Simulation {
+            mod_val: monkeys.iter().fold(1_u64, |v, monkey| v * monkey.test),
+            monkey_activity: vec![0; monkeys.len()],
+            monkeys,
+            worry_reduction,
+            round: 0,
+            trace: None,
+            starting_monkey,
+        }
+    }
+
+    pub fn with_trace(mut self) -> Simulation {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn do_round(&mut self) -> Result<(), WorryError> {
+        self.round += 1;
+        for i in 0..self.monkeys.len() {
+            let monkey: &mut Monkey = self.monkeys.get_mut(i).expect("Couldn't get monkey");
+            let throws = monkey
+                .items
+                .drain(..)
+                .map(|item| {
+                    let worry = monkey
+                        .op
+                        .eval(item.worry)
+                        .map_err(|error| WorryError::Eval {
+                            round: self.round,
+                            monkey: i,
+                            item: item.id,
+                            old: item.worry,
+                            error,
+                        })?
+                        / self.worry_reduction;
+                    let divisible = worry.is_multiple_of(monkey.test);
+                    Ok(Throw {
+                        round: self.round,
+                        from: i,
+                        to: if divisible {
+                            monkey.test_result.0
+                        } else {
+                            monkey.test_result.1
+                        },
+                        item: item.id,
+                        worry,
+                        reduced: if self.worry_reduction == 1 {
+                            worry % self.mod_val
+                        } else {
+                            worry
+                        },
+                        test: monkey.test,
+                        divisible,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            self.monkey_activity[i] += throws.len();
+
+            for throw in &throws {
+                self.monkeys
+                    .get_mut(throw.to)
                     .expect("Couldn't get destination monkey")
                     .items
-                    .push(item);
-            });
+                    .push(Item {
+                        id: throw.item,
+                        worry: throw.reduced,
+                    });
+            }
+            if let Some(trace) = &mut self.trace {
+                trace.extend(throws);
+            }
+        }
+        Ok(())
     }
-}
 
-fn part1(mut monkeys: Vec<Monkey>) -> usize {
-    let mut monkey_activity: Vec<usize> = vec![0; monkeys.len()];
+    pub fn run(&mut self, num_rounds: usize) -> Result<(), WorryError> {
+        for _ in 0..num_rounds {
+            self.do_round()?;
+        }
+        Ok(())
+    }
 
-    //println!("Monkeys: {:?}", monkeys);
-    static NUM_ROUNDS: usize = 20;
-    for _ in 0..NUM_ROUNDS {
-        do_round(&mut monkeys, 3, &mut monkey_activity);
-        //println!("Monkeys after round {}: {:?}", i, monkeys);
+    // Every throw of one item, in order
+    pub fn trajectory(&self, item: usize) -> Vec<Throw> {
+        self.trace
+            .as_ref()
+            .expect("Trajectories need a traced simulation")
+            .iter()
+            .filter(|throw| throw.item == item)
+            .copied()
+            .collect()
+    }
+
+    // Which monkey held an item at the end of a round, 0 being the start
+    pub fn holder_after(&self, item: usize, round: usize) -> usize {
+        assert!(round <= self.round, "Round {} hasn't been simulated", round);
+        self.trajectory(item)
+            .iter()
+            .rev()
+            .find(|throw| throw.round <= round)
+            .map_or(self.starting_monkey[item], |throw| throw.to)
+    }
+
+    pub fn monkey_business(&self) -> usize {
+        let mut top_monkeys = self.monkey_activity.clone();
+        top_monkeys.sort_by(|a, b| usize::cmp(b, a));
+        top_monkeys[0] * top_monkeys[1]
+    }
+
+    pub fn print_activity(&self) {
+        self.monkey_activity
+            .iter()
+            .enumerate()
+            .for_each(|(i, activity)| {
+                println!("Monkey {} inspected {} items", i, activity);
+            });
     }
-    let mut top_monkeys = monkey_activity.iter().collect::<Vec<_>>();
-    top_monkeys.sort_by(|a, b| usize::cmp(b, a));
-    monkey_activity
-        .iter()
-        .enumerate()
-        .for_each(|(i, activity)| {
-            println!("Monkey {} inspected {} items", i, activity);
-        });
-    top_monkeys[0] * top_monkeys[1]
 }
 
-fn part2(mut monkeys: Vec<Monkey>) -> usize {
-    let mut monkey_activity: Vec<usize> = vec![0; monkeys.len()];
+pub fn part1(monkeys: Vec<Monkey>) -> Result<usize, WorryError> {
+    static NUM_ROUNDS: usize = 20;
+    let mut simulation = Simulation::new(monkeys, 3);
+    simulation.run(NUM_ROUNDS)?;
+    simulation.print_activity();
+    Ok(simulation.monkey_business())
+}
 
-    //println!("Monkeys: {:?}", monkeys);
+pub fn part2(monkeys: Vec<Monkey>) -> Result<usize, WorryError> {
     static NUM_ROUNDS: usize = 10_000;
-    for _ in 0..NUM_ROUNDS {
-        do_round(&mut monkeys, 1, &mut monkey_activity);
-        //println!("Monkeys after round {}: {:?}", i, monkeys);
-    }
-    let mut top_monkeys = monkey_activity.iter().collect::<Vec<_>>();
-    top_monkeys.sort_by(|a, b| usize::cmp(b, a));
-    monkey_activity
+    if let Some(monkey) = monkeys
         .iter()
-        .enumerate()
-        .for_each(|(i, activity)| {
-            println!("Monkey {} inspected {} items", i, activity);
-        });
-    top_monkeys[0] * top_monkeys[1]
+        .position(|monkey| !monkey.op.preserves_residues())
+    {
+        return Err(WorryError::ResiduesNotKept { monkey });
+    }
+    let mut simulation = Simulation::new(monkeys, 1);
+    simulation.run(NUM_ROUNDS)?;
+    simulation.print_activity();
+    Ok(simulation.monkey_business())
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_text(text: &str) -> Vec<Monkey> {
+        parse_monkeys(&text.lines().map(str::to_string).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn example() {
+        let monkeys = parse_text(include_str!("example.txt"));
+        assert_eq!(part1(monkeys.to_vec()), Ok(10605));
+        assert_eq!(part2(monkeys), Ok(2713310158));
+    }
+
+    #[test]
+    fn parses_blocks_between_blank_lines() {
+        let monkeys = parse_text(
+            "\n\
+             Monkey 0:\n\
+             \x20 Starting items: 5, 7\n\
+             \x20 Operation: new = (old + 2) * old\n\
+             \x20 Test: divisible by 3\n\
+             \x20   If true: throw to monkey 1\n\
+             \x20   If false: throw to monkey 2\n\
+             \n\
+             \x20 \n\
+             Monkey 1:\n\
+             \x20 Starting items:\n\
+             \x20 Operation: new = old - 1\n\
+             \x20 Test: divisible by 5\n\
+             \x20   If true: throw to monkey 2\n\
+             \x20   If false: throw to monkey 0\n\
+             \n\
+             Monkey 2:\n\
+             \x20 Starting items: 11\n\
+             \x20 Operation: new = 4\n\
+             \x20 Test: divisible by 7\n\
+             \x20   If true: throw to monkey 0\n\
+             \x20   If false: throw to monkey 1\n",
+        );
+        let old = || Box::new(Operation::Old);
+        assert_eq!(
+            monkeys,
+            vec![
+                Monkey {
+                    items: vec![Item { id: 0, worry: 5 }, Item { id: 1, worry: 7 }],
+                    op: Operation::Multiply(
+                        Box::new(Operation::Add(old(), Box::new(Operation::Const(2)))),
+                        old()
+                    ),
+                    test: 3,
+                    test_result: (1, 2),
+                },
+                Monkey {
+                    items: vec![],
+                    op: Operation::Subtract(old(), Box::new(Operation::Const(1))),
+                    test: 5,
+                    test_result: (2, 0),
+                },
+                Monkey {
+                    items: vec![Item { id: 2, worry: 11 }],
+                    op: Operation::Const(4),
+                    test: 7,
+                    test_result: (0, 1),
+                },
+            ]
+        );
+    }
 
-    let monkeys = parse_monkeys(&lines);
+    #[test]
+    fn traces_trajectories() {
+        let monkeys = parse_text(include_str!("example.txt"));
+        let mut simulation = Simulation::new(monkeys, 3).with_trace();
+        simulation.run(2).unwrap();
 
-    println!("Part 1: {}", part1(monkeys.to_vec()));
-    println!("Part 2: {}", part2(monkeys.to_vec()));
+        // Item 0 starts as 79 with monkey 0, which passes it on to monkey 3
+        // and then monkey 1 in the first round
+        let trajectory = simulation.trajectory(0);
+        assert_eq!(
+            trajectory[..2],
+            [
+                Throw {
+                    round: 1,
+                    from: 0,
+                    to: 3,
+                    item: 0,
+                    worry: 500,
+                    reduced: 500,
+                    test: 23,
+                    divisible: false,
+                },
+                Throw {
+                    round: 1,
+                    from: 3,
+                    to: 1,
+                    item: 0,
+                    worry: 167,
+                    reduced: 167,
+                    test: 17,
+                    divisible: false,
+                },
+            ]
+        );
+        // Then 57 is divisible by monkey 1's 19
+        assert_eq!(
+            trajectory[2..]
+                .iter()
+                .map(|throw| (throw.round, throw.from, throw.to, throw.worry))
+                .collect::<Vec<_>>(),
+            [(2, 1, 2, 57), (2, 2, 3, 1083), (2, 3, 1, 362)]
+        );
+        assert_eq!(simulation.holder_after(0, 0), 0);
+        assert_eq!(simulation.holder_after(0, 1), 1);
+        assert_eq!(simulation.holder_after(0, 2), 1);
+        // Item 9 is the 74 monkey 3 starts with
+        assert_eq!(simulation.holder_after(9, 0), 3);
+        assert_eq!(simulation.trajectory(9)[0].to, 1);
+        // Every inspection is traced
+        assert_eq!(
+            simulation.trace.as_ref().map(Vec::len),
+            Some(simulation.monkey_activity.iter().sum())
+        );
+    }
+
+    #[test]
+    fn trace_keeps_reduced_worry_without_relief() {
+        let monkeys = parse_text(include_str!("example.txt"));
+        let mut simulation = Simulation::new(monkeys, 1).with_trace();
+        simulation.run(3).unwrap();
+        for throw in simulation.trace.as_ref().unwrap() {
+            assert_eq!(throw.reduced, throw.worry % simulation.mod_val);
+            assert_eq!(throw.divisible, throw.worry % throw.test == 0);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let eval = |expr: &str, old: u64| Operation::parse(expr).eval(old);
+        assert_eq!(eval("(old - 2) / (old - 1)", 5), Ok(0));
+        assert_eq!(eval("old - 2", 1), Err(EvalError::Negative));
+        assert_eq!(eval("old / (old - 1)", 1), Err(EvalError::DivideByZero));
+        assert_eq!(eval("old * old", 1 << 32), Err(EvalError::Overflow));
+        assert_eq!(eval("old + 1", u64::MAX), Err(EvalError::Overflow));
+
+        let mut monkeys = parse_text(include_str!("example.txt"));
+        monkeys[2].op = Operation::parse("old / 2");
+        assert_eq!(
+            part2(monkeys.to_vec()),
+            Err(WorryError::ResiduesNotKept { monkey: 2 })
+        );
+
+        // Monkey 1's items are 54, 65, 75 and 74 before any are thrown to it
+        monkeys[1].op = Operation::parse("old - 60");
+        assert_eq!(
+            part1(monkeys),
+            Err(WorryError::Eval {
+                round: 1,
+                monkey: 1,
+                item: 2,
+                old: 54,
+                error: EvalError::Negative,
+            })
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use std::env;
+use std::io;
+use std::process;
+
+use day11::{parse_monkeys, part1, part2, Simulation};
+
+// Prints every throw of one item during part 1's rounds
+fn print_trajectory(simulation: &Simulation, item: usize) {
+    eprintln!(
+        "Item {} starts with monkey {}",
+        item,
+        simulation.holder_after(item, 0)
+    );
+    for throw in simulation.trajectory(item) {
+        eprintln!(
+            "Round {}: monkey {} -> {}, worry {}{}, {} divisible by {}",
+            throw.round,
+            throw.from,
+            throw.to,
+            throw.worry,
+            if throw.reduced == throw.worry {
+                String::new()
+            } else {
+                format!(" reduced to {} (mod {})", throw.reduced, simulation.mod_val)
+            },
+            if throw.divisible { "is" } else { "not" },
+            throw.test
+        );
+    }
+}
+
+fn main() {
+    // `trace=N` follows item N, numbered across the starting lists
+    let trace_item = env::args()
+        .skip(1)
+        .find_map(|arg| arg.strip_prefix("trace=").map(str::to_string))
+        .map(|item| item.parse::<usize>().expect("Couldn't parse item to trace"));
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let monkeys = parse_monkeys(&lines);
+
+    if let Some(item) = trace_item {
+        let mut simulation = Simulation::new(monkeys.to_vec(), 3).with_trace();
+        // Whatever stopped the run is reported by part 1
+        let _ = simulation.run(20);
+        print_trajectory(&simulation, item);
+    }
+
+    match part1(monkeys.to_vec()) {
+        Ok(business) => println!("Part 1: {}", business),
+        Err(err) => {
+            eprintln!("Part 1 failed: {}", err);
+            process::exit(1);
+        }
+    }
+    match part2(monkeys.to_vec()) {
+        Ok(business) => println!("Part 2: {}", business),
+        Err(err) => {
+            eprintln!("Part 2 failed: {}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -1,35 +1,34 @@
-use std::collections::HashMap;
-use std::io;
+use std::collections::VecDeque;
 
-fn parse(lines: &Vec<String>) -> Vec<Vec<char>> {
+pub fn parse(lines: &[String]) -> Vec<Vec<char>> {
     lines
-        .into_iter()
+        .iter()
+        .filter(|line| !line.is_empty())
         .map(|line| line.chars().collect())
         .collect()
 }
 
-fn find_char_poss(grid: &Vec<Vec<char>>, needle: char) -> Vec<(usize, usize)> {
-    grid.into_iter()
+pub fn find_char_poss(grid: &[Vec<char>], needle: char) -> Vec<(usize, usize)> {
+    grid.iter()
         .enumerate()
-        .filter_map(|(start_y, row)| {
-            if let Some(start_x) = row.into_iter().position(|c| *c == needle) {
-                Some((start_x, start_y))
-            } else {
-                None
-            }
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(_, c)| **c == needle)
+                .map(move |(x, _)| (x, y))
         })
         .collect()
 }
 
-fn find_char_pos(grid: &Vec<Vec<char>>, needle: char) -> (usize, usize) {
-    find_char_poss(&grid, needle)[0]
+pub fn find_char_pos(grid: &[Vec<char>], needle: char) -> (usize, usize) {
+    find_char_poss(grid, needle)[0]
 }
 
-fn add(lhs: (isize, isize), rhs: (isize, isize)) -> (isize, isize) {
+pub fn add(lhs: (isize, isize), rhs: (isize, isize)) -> (isize, isize) {
     (lhs.0 + rhs.0, lhs.1 + rhs.1)
 }
 
-fn char_to_height(c: char) -> isize {
+pub fn char_to_height(c: char) -> isize {
     match c {
         'a'..='z' => Some(c),
         'S' => Some('a'),
@@ -39,74 +38,188 @@ fn char_to_height(c: char) -> isize {
     .expect("Couldn't get height for char") as isize
 }
 
-fn path_for_starting_pos(grid: &Vec<Vec<char>>, start_pos: (usize, usize)) -> Option<usize> {
-    let height: usize = grid.len();
-    let width: usize = grid[0].len();
-    let end_pos = find_char_pos(&grid, 'E');
-    let mut checked_paths = HashMap::<(usize, usize), usize>::new();
-    let mut paths_to_check = [(start_pos, 0)].into_iter().collect::<HashMap<_, _>>();
-    while !checked_paths.contains_key(&end_pos) && paths_to_check.len() != 0 {
-        let new_paths_to_check = paths_to_check
+// Whether a single step from one square to the other is allowed
+pub fn can_climb(grid: &[Vec<char>], from: (usize, usize), to: (usize, usize)) -> bool {
+    char_to_height(grid[to.1][to.0]) - 1 <= char_to_height(grid[from.1][from.0])
+}
+
+fn neighbours(grid: &[Vec<char>], pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let (width, height) = (grid[0].len() as isize, grid.len() as isize);
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .map(move |dir| add(dir, (pos.0 as isize, pos.1 as isize)))
+        .filter(move |pos| (0..width).contains(&pos.0) && (0..height).contains(&pos.1))
+        .map(|pos| (pos.0 as usize, pos.1 as usize))
+}
+
+// The fewest steps from every square to the end, found with one search
+// walking backwards from the end. None where the end can't be reached.
+pub struct DistanceField {
+    pub end: (usize, usize),
+    distances: Vec<Vec<Option<usize>>>,
+}
+
+impl DistanceField {
+    pub fn new(grid: &[Vec<char>]) -> DistanceField {
+        let end = find_char_pos(grid, 'E');
+        let mut distances = vec![vec![None; grid[0].len()]; grid.len()];
+        distances[end.1][end.0] = Some(0);
+        let mut to_check = VecDeque::from([(end, 0)]);
+        while let Some((pos, steps)) = to_check.pop_front() {
+            for prev_pos in neighbours(grid, pos) {
+                if distances[prev_pos.1][prev_pos.0].is_none() && can_climb(grid, prev_pos, pos) {
+                    distances[prev_pos.1][prev_pos.0] = Some(steps + 1);
+                    to_check.push_back((prev_pos, steps + 1));
+                }
+            }
+        }
+        DistanceField { end, distances }
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<usize> {
+        self.distances[pos.1][pos.0]
+    }
+
+    // The closest of the starting squares and its distance
+    pub fn nearest(&self, starts: &[(usize, usize)]) -> Option<((usize, usize), usize)> {
+        starts
             .iter()
-            .map(|(path_pos, path_steps)| {
-                [(1, 0), (-1, 0), (0, 1), (0, -1)]
-                    .into_iter()
-                    .map(|dir| add(dir, (path_pos.0 as isize, path_pos.1 as isize)))
-                    .filter_map(|surrounding_pos| {
-                        if (0..width as isize).contains(&surrounding_pos.0)
-                            && (0..height as isize).contains(&surrounding_pos.1)
-                        {
-                            let surrounding_pos =
-                                (surrounding_pos.0 as usize, surrounding_pos.1 as usize);
-
-                            if checked_paths.contains_key(&surrounding_pos) {
-                                None
-                            } else {
-                                let surrounding_height =
-                                    char_to_height(grid[surrounding_pos.1][surrounding_pos.0]);
-                                let c_height = char_to_height(grid[path_pos.1][path_pos.0]);
-                                if surrounding_height - 1 <= c_height {
-                                    Some((surrounding_pos, path_steps + 1))
-                                } else {
-                                    None
-                                }
-                            }
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<HashMap<_, _>>()
-            })
-            .reduce(|mut ret, m| {
-                ret.extend(m);
-                ret
-            })
-            .expect("Couldn't get new paths to check");
-        checked_paths.extend(paths_to_check.drain());
-
-        paths_to_check = new_paths_to_check;
+            .filter_map(|&pos| self.get(pos).map(|steps| (pos, steps)))
+            .min_by_key(|(_, steps)| *steps)
+    }
+
+    // A shortest path from the start to the end, both included. Each step
+    // goes to a neighbour one closer to the end, which always exists along a
+    // shortest path.
+    pub fn path(&self, grid: &[Vec<char>], start: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let mut steps = self.get(start)?;
+        let mut path = vec![start];
+        let mut pos = start;
+        while steps > 0 {
+            pos = neighbours(grid, pos)
+                .find(|&next| self.get(next) == Some(steps - 1) && can_climb(grid, pos, next))
+                .expect("Distances should lead to the end");
+            steps -= 1;
+            path.push(pos);
+        }
+        Some(path)
+    }
+}
+
+// Draws the heightmap shaded from dark valleys to bright peaks, with the path
+// marked by arrows like in the puzzle
+pub fn render(grid: &[Vec<char>], path: &[(usize, usize)]) -> String {
+    let mut marks = vec![vec![None; grid[0].len()]; grid.len()];
+    for pair in path.windows(2) {
+        let ((x, y), (next_x, next_y)) = (pair[0], pair[1]);
+        marks[y][x] = Some(
+            match (next_x as isize - x as isize, next_y as isize - y as isize) {
+                (1, 0) => '>',
+                (-1, 0) => '<',
+                (0, 1) => 'v',
+                _ => '^',
+            },
+        );
     }
-    checked_paths.get(&end_pos).copied()
+    if let Some(&(x, y)) = path.last() {
+        marks[y][x] = Some('E');
+    }
+
+    let mut out = String::new();
+    for (row, row_marks) in grid.iter().zip(marks) {
+        for (&c, mark) in row.iter().zip(row_marks) {
+            // The 24 greys at the end of the 256 colour palette
+            let shade = 232 + (char_to_height(c) - 'a' as isize) * 23 / 25;
+            let fg = match mark {
+                Some(_) => "1;31",
+                None if shade > 243 => "38;5;232",
+                None => "38;5;255",
+            };
+            out.push_str(&format!(
+                "\x1b[0;48;5;{};{}m{}",
+                shade,
+                fg,
+                mark.unwrap_or(c)
+            ));
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
 }
 
-fn part1(grid: &Vec<Vec<char>>) -> usize {
-    let start_pos = find_char_pos(&grid, 'S');
-    path_for_starting_pos(grid, start_pos).expect("Couldn't get path from start pos")
+pub fn part1(grid: &[Vec<char>], field: &DistanceField) -> usize {
+    field
+        .get(find_char_pos(grid, 'S'))
+        .expect("Couldn't get path from start pos")
 }
 
-fn part2(grid: &Vec<Vec<char>>) -> usize {
-    find_char_poss(&grid, 'a')
+// Every square at the lowest elevation, which includes S
+pub fn lowest_squares(grid: &[Vec<char>]) -> Vec<(usize, usize)> {
+    find_char_poss(grid, 'a')
         .into_iter()
-        .chain(find_char_poss(&grid, 'S'))
-        .filter_map(|start_pos| path_for_starting_pos(&grid, start_pos))
-        .min()
+        .chain(find_char_poss(grid, 'S'))
+        .collect()
+}
+
+pub fn part2(grid: &[Vec<char>], field: &DistanceField) -> usize {
+    field
+        .nearest(&lowest_squares(grid))
+        .map(|(_, steps)| steps)
         .expect("Couldn't get shortest path")
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
-    let grid = parse(&lines);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A path has to be as long as the distance says and only take steps
+    // that can be climbed
+    fn check_path(grid: &[Vec<char>], field: &DistanceField, start: (usize, usize)) {
+        let path = field.path(grid, start).unwrap();
+        assert_eq!(path.len(), field.get(start).unwrap() + 1);
+        assert_eq!(path[0], start);
+        assert_eq!(*path.last().unwrap(), field.end);
+        for pair in path.windows(2) {
+            let ((x, y), (next_x, next_y)) = (pair[0], pair[1]);
+            assert_eq!(x.abs_diff(next_x) + y.abs_diff(next_y), 1, "{:?}", pair);
+            assert!(can_climb(grid, pair[0], pair[1]), "{:?}", pair);
+        }
+    }
 
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
+    #[test]
+    fn paths_follow_distances() {
+        let example: Vec<String> = ["Sabqponm", "abcryxxl", "accszExk", "acctuvwj", "abdefghi"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        // S and E in different places, climbs that are too steep, dead ends
+        // and squares that can't reach the end at all
+        let grids = [
+            vec!["SE"],
+            vec!["Sbcdefghijklmnopqrstuvwxy", "zzzzzzzzzzzzzzzzzzzzzzzzE"],
+            vec!["abcde", "aSkfg", "zzjih", "Eyxwv"],
+            vec!["aaaaaaaa", "azzzzzza", "azEyxwza", "azzzzvza", "Sbcdefgu"],
+            vec!["mmmmmz", "mnzzoz", "mzEyzp", "mmmmnq", "Szzzzz"],
+            vec!["Sb", "qc", "pd", "oe", "nf", "mg", "lh", "ki", "jj", "zE"],
+        ];
+        let grids = grids.map(|lines| {
+            parse(
+                &lines
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>(),
+            )
+        });
+        for grid in [parse(&example)].into_iter().chain(grids) {
+            // Every square that can reach the end, which always includes E
+            let field = DistanceField::new(&grid);
+            for (y, row) in grid.iter().enumerate() {
+                for x in 0..row.len() {
+                    if field.get((x, y)).is_some() {
+                        check_path(&grid, &field, (x, y));
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use std::env;
+use std::io;
+
+use day12::{find_char_pos, lowest_squares, parse, part1, part2, render, DistanceField};
+
+fn main() {
+    // `path` draws the shortest route from S and from the best square at the
+    // lowest elevation, the ones parts 1 and 2 count
+    let show_path = env::args().skip(1).any(|arg| arg == "path");
+
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+    let grid = parse(&lines);
+    let field = DistanceField::new(&grid);
+
+    if show_path {
+        let best_start = field.nearest(&lowest_squares(&grid)).map(|(pos, _)| pos);
+        for start in [Some(find_char_pos(&grid, 'S')), best_start]
+            .into_iter()
+            .flatten()
+        {
+            let path = field.path(&grid, start).expect("Start can reach the end");
+            println!("Path from {:?} in {} steps:", start, path.len() - 1);
+            print!("{}", render(&grid, &path));
+        }
+    }
+
+    println!("Part 1: {}", part1(&grid, &field));
+    println!("Part 2: {}", part2(&grid, &field));
+}
@@ -1,67 +1,179 @@
-use itertools::{EitherOrBoth::*, Itertools};
-use serde_json::{json, Value};
-use std::cmp;
-use std::io;
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 
-fn parse_lines(lines: &Vec<String>) -> Vec<Value> {
-    lines
-        .iter()
-        .filter_map(|line| {
-            let line = line.trim();
-            if !line.is_empty() {
-                Some(serde_json::from_str(line).expect("Couldn't parse line"))
-            } else {
-                None
-            }
+// Equality is structural, so 1 and [1] are different packets even though
+// they're in the right order either way. Use `Packet::compare` for the order.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Packet {
+    Int(u64),
+    List(Vec<Packet>),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    // Byte offset into the packet's text
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.pos + 1)
+    }
+}
+
+// Reads packets straight out of the input bytes, without tokenizing them first
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        Err(ParseError {
+            pos: self.pos,
+            message: message.to_string(),
         })
-        .collect()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.input.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(&format!("Expected '{}'", byte as char))
+        }
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        match self.input.get(self.pos) {
+            Some(b'[') => self.list(),
+            Some(b'0'..=b'9') => self.int(),
+            Some(_) => self.error("Expected '[' or a digit"),
+            None => self.error("Unexpected end of packet"),
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        if self.input.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(Packet::List(items));
+        }
+        loop {
+            items.push(self.packet()?);
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return self.error("Expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn int(&mut self) -> Result<Packet, ParseError> {
+        let start = self.pos;
+        let mut val: u64 = 0;
+        while let Some(digit @ b'0'..=b'9') = self.input.get(self.pos) {
+            val = match val
+                .checked_mul(10)
+                .and_then(|val| val.checked_add((digit - b'0') as u64))
+            {
+                Some(val) => val,
+                None => {
+                    self.pos = start;
+                    return self.error("Integer too large");
+                }
+            };
+            self.pos += 1;
+        }
+        Ok(Packet::Int(val))
+    }
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+    fn from_str(input: &str) -> Result<Packet, Self::Err> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            pos: 0,
+        };
+        let packet = parser.list()?;
+        if parser.pos != input.len() {
+            return parser.error("Trailing characters after packet");
+        }
+        Ok(packet)
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Int(val) => write!(f, "{}", val),
+            Packet::List(items) => write!(f, "[{}]", items.iter().join(",")),
+        }
+    }
 }
 
-fn order(lhs: &Value, rhs: &Value) -> cmp::Ordering {
-    match (lhs, rhs) {
-        (Value::Number(x), Value::Number(y)) => i64::cmp(
-            &x.as_i64().expect("Couldn't parse int x"),
-            &y.as_i64().expect("Couldn't parse int y"),
-        ),
-        (Value::Number(x), Value::Array(_)) => order(&json!(vec![x]), rhs),
-        (Value::Array(_), Value::Number(y)) => order(lhs, &json!(vec![y])),
-        (Value::Array(a), Value::Array(b)) => {
-            a.iter()
-                .zip_longest(b.iter())
-                .fold(cmp::Ordering::Equal, |ordering, pair| match ordering {
-                    cmp::Ordering::Less => ordering,
-                    cmp::Ordering::Greater => ordering,
-                    _ => match pair {
-                        Both(x, y) => order(x, y),
-                        Right(_) => cmp::Ordering::Less,
-                        Left(_) => cmp::Ordering::Greater,
-                    },
-                })
+impl Packet {
+    // Integers compare by value and lists element by element, with the
+    // shorter list first on a tie. An integer compared with a list acts as a
+    // one item list.
+    pub fn compare(&self, other: &Packet) -> Ordering {
+        match (self, other) {
+            (Packet::Int(x), Packet::Int(y)) => x.cmp(y),
+            (Packet::List(a), Packet::List(b)) => compare_lists(a, b),
+            (Packet::Int(_), Packet::List(b)) => compare_lists(std::slice::from_ref(self), b),
+            (Packet::List(a), Packet::Int(_)) => compare_lists(a, std::slice::from_ref(other)),
         }
-        _ => panic!("Values aren't arrays and numbers"),
     }
 }
 
-fn part1(parsed: &Vec<Value>) -> usize {
+fn compare_lists(a: &[Packet], b: &[Packet]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.compare(y))
+        .find(|order| order.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+pub fn parse_lines(lines: &[String]) -> Vec<Packet> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .unwrap_or_else(|err| panic!("Couldn't parse line {}: {}", i + 1, err))
+        })
+        .collect()
+}
+
+pub fn part1(parsed: &[Packet]) -> usize {
     parsed
-        .into_iter()
+        .iter()
         .tuples::<(_, _)>()
-        .map(|pair| order(pair.0, pair.1))
         .enumerate()
-        .filter(|(_, b)| *b == cmp::Ordering::Less)
+        .filter(|(_, (lhs, rhs))| lhs.compare(rhs) == Ordering::Less)
         .map(|(i, _)| i + 1)
         .sum()
 }
 
-fn part2(mut parsed: Vec<Value>) -> usize {
-    parsed.sort_by(order);
-    [vec![2], vec![6]]
+pub fn part2(mut parsed: Vec<Packet>) -> usize {
+    parsed.sort_by(Packet::compare);
+    ["[[2]]", "[[6]]"]
         .iter()
-        .map(|i| json!(i))
+        .map(|divider| divider.parse::<Packet>().expect("Couldn't parse divider"))
         .enumerate()
         .map(|(i, divider)| {
             i + 1
-                + match parsed.binary_search_by(|i| order(i, &divider)) {
+                + match parsed.binary_search_by(|packet| packet.compare(&divider)) {
                     Ok(pos) => pos,
                     Err(pos) => pos,
                 }
@@ -69,11 +181,82 @@ fn part2(mut parsed: Vec<Value>) -> usize {
         .product()
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(text: &str) -> Packet {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let lines: Vec<String> = include_str!("example.txt")
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let parsed = parse_lines(&lines);
+        assert_eq!(part1(&parsed), 13);
+        assert_eq!(part2(parsed), 140);
+    }
 
-    let parsed = parse_lines(&lines);
+    #[test]
+    fn equality_is_structural() {
+        assert_ne!(packet("[1]"), packet("[[1]]"));
+        assert_eq!(packet("[1]").compare(&packet("[[1]]")), Ordering::Equal);
+        assert_eq!(packet("[[],[3]]"), packet("[[],[3]]"));
+        assert_eq!(packet("[[]]").compare(&packet("[]")), Ordering::Greater);
+        assert_eq!(packet("[2,3]").compare(&packet("[[2],4]")), Ordering::Less);
+    }
 
-    println!("Part 1: {}", part1(&parsed));
-    println!("Part 2: {}", part2(parsed));
+    #[test]
+    fn display_round_trips() {
+        for text in [
+            "[]",
+            "[[]]",
+            "[0]",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[[[]],[],10,[18446744073709551615]]",
+        ] {
+            assert_eq!(packet(text).to_string(), text);
+        }
+        let lines: Vec<String> = include_str!("example.txt")
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let printed: Vec<String> = parse_lines(&lines).iter().map(Packet::to_string).collect();
+        assert_eq!(
+            printed,
+            lines
+                .iter()
+                .filter(|line| !line.is_empty())
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_positions() {
+        let error = |text: &str| {
+            let err = text.parse::<Packet>().unwrap_err();
+            (err.pos, err.message)
+        };
+        assert_eq!(error(""), (0, "Expected '['".to_string()));
+        assert_eq!(error("5"), (0, "Expected '['".to_string()));
+        assert_eq!(error("[1,2"), (4, "Expected ',' or ']'".to_string()));
+        assert_eq!(error("[1,,2]"), (3, "Expected '[' or a digit".to_string()));
+        assert_eq!(error("[[1],"), (5, "Unexpected end of packet".to_string()));
+        assert_eq!(
+            error("[1] "),
+            (3, "Trailing characters after packet".to_string())
+        );
+        assert_eq!(
+            error("[1,18446744073709551616]"),
+            (3, "Integer too large".to_string())
+        );
+        assert_eq!(
+            "[1;2]".parse::<Packet>().unwrap_err().to_string(),
+            "Expected ',' or ']' at column 3"
+        );
+    }
 }
@@ -0,0 +1,12 @@
+use std::io;
+
+use day13::{parse_lines, part1, part2};
+
+fn main() {
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let parsed = parse_lines(&lines);
+
+    println!("Part 1: {}", part1(&parsed));
+    println!("Part 2: {}", part2(parsed));
+}
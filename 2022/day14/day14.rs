@@ -1,45 +1,53 @@
 use itertools::Itertools;
 use std::cmp::{max, min};
 use std::collections::HashSet;
-use std::io;
 
-type Point = (isize, isize);
+pub type Point = (isize, isize);
 
 #[derive(PartialEq, Eq, Clone)]
-struct Bounds {
-    minx: isize,
-    maxx: isize,
-    miny: isize,
-    maxy: isize,
+pub struct Bounds {
+    pub minx: isize,
+    pub maxx: isize,
+    pub miny: isize,
+    pub maxy: isize,
 }
 
 #[derive(PartialEq, Eq, Clone)]
-struct Grid {
-    rocks: HashSet<Point>,
-    rock_bounds: Bounds,
-    sands: HashSet<Point>,
-    sand_bounds: Bounds,
-    pt2: bool,
+pub struct Grid {
+    pub rocks: HashSet<Point>,
+    pub rock_bounds: Bounds,
+    pub sands: HashSet<Point>,
+    pub sand_bounds: Bounds,
+    pub sources: Vec<Point>,
+    pub bottom: Bottom,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Bottom {
+    // Sand falling past the lowest rock is lost
+    Abyss,
+    // An endless floor this many rows below the lowest rock
+    Floor { depth: isize },
 }
 
 #[derive(PartialEq, Eq, Hash, Clone)]
-enum Occupation {
+pub enum Occupation {
     Rock,
     Sand,
     Air,
 }
 
-fn add(p1: Point, p2: Point) -> Point {
+pub fn add(p1: Point, p2: Point) -> Point {
     (p1.0 + p2.0, p1.1 + p2.1)
 }
 
 impl Grid {
-    fn parse(lines: &Vec<String>) -> Grid {
+    pub fn parse(lines: &[String]) -> Grid {
         let merge_hashsets =
             |hs1: HashSet<Point>, hs2: HashSet<Point>| hs1.union(&hs2).cloned().collect();
         Self::new(
             lines
-                .into_iter()
+                .iter()
                 .filter_map(|line| {
                     line.split("->")
                         .map(|point| -> Point {
@@ -74,7 +82,7 @@ impl Grid {
         )
     }
 
-    fn new(rocks: HashSet<Point>) -> Grid {
+    pub fn new(rocks: HashSet<Point>) -> Grid {
         let rock_bounds = Bounds {
             minx: rocks
                 .iter()
@@ -102,18 +110,29 @@ impl Grid {
             rock_bounds,
             rocks,
             sands: [].into_iter().collect(),
-            pt2: false,
+            sources: vec![(500, 0)],
+            bottom: Bottom::Abyss,
         }
     }
 
-    fn set_pt2(&mut self, pt2: bool) {
-        self.pt2 = pt2;
+    pub fn set_bottom(&mut self, bottom: Bottom) {
+        self.bottom = bottom;
     }
 
-    fn position_occupied_by(&self, p: Point) -> Occupation {
-        if self.pt2 && p.1 >= self.rock_bounds.maxy + 2 {
-            Occupation::Rock
-        } else if self.rocks.contains(&p) {
+    pub fn floor_y(&self) -> Option<isize> {
+        match self.bottom {
+            Bottom::Abyss => None,
+            Bottom::Floor { depth } => Some(self.rock_bounds.maxy + depth),
+        }
+    }
+
+    // Lowest y a falling grain can still come to rest at
+    fn lowest_y(&self) -> isize {
+        self.floor_y().unwrap_or(self.rock_bounds.maxy)
+    }
+
+    pub fn position_occupied_by(&self, p: Point) -> Occupation {
+        if self.floor_y().is_some_and(|floor| p.1 >= floor) || self.rocks.contains(&p) {
             Occupation::Rock
         } else if self.sands.contains(&p) {
             Occupation::Sand
@@ -122,9 +141,9 @@ impl Grid {
         }
     }
 
-    fn drop_sand(&mut self, start_pos: Point) -> Option<Point> {
+    pub fn drop_sand(&mut self, start_pos: Point) -> Option<Point> {
         let mut sand_pos: Point = start_pos;
-        while sand_pos.1 <= self.rock_bounds.maxy + 2 {
+        while sand_pos.1 <= self.lowest_y() {
             if let Some(dest) = [(0, 1), (-1, 1), (1, 1)]
                 .into_iter()
                 .map(|p| add(sand_pos, p))
@@ -143,11 +162,105 @@ impl Grid {
         None
     }
 
-    fn print(&self) {
-        let minx = min(self.rock_bounds.minx, self.sand_bounds.minx);
-        let miny = min(self.rock_bounds.miny, self.sand_bounds.miny);
-        let maxx = max(self.rock_bounds.maxx, self.sand_bounds.maxx);
-        let maxy = max(self.rock_bounds.maxy, self.sand_bounds.maxy);
+    // Drops grains from each source in turn until one falls into the abyss
+    // or every source is blocked, calling back after each grain settles
+    pub fn simulate<F: FnMut(&Grid, Point)>(&mut self, mut on_settle: F) -> usize {
+        let mut active: Vec<Point> = self
+            .sources
+            .iter()
+            .copied()
+            .filter(|&source| self.position_occupied_by(source) == Occupation::Air)
+            .collect();
+        while !active.is_empty() {
+            let mut still_active = Vec::new();
+            for source in active {
+                if self.position_occupied_by(source) != Occupation::Air {
+                    continue;
+                }
+                match self.drop_sand(source) {
+                    None => return self.sands.len(),
+                    Some(settled_pos) => {
+                        on_settle(self, settled_pos);
+                        if settled_pos != source {
+                            still_active.push(source);
+                        }
+                    }
+                }
+            }
+            active = still_active;
+        }
+        self.sands.len()
+    }
+
+    // With a floor every grain comes to rest, and the sand ends up filling
+    // exactly the cells a grain could reach. Those can be found a row at a
+    // time, as a cell is reachable if any of the three above it are. Without
+    // a floor the order grains arrive in matters, so there's no shortcut.
+    pub fn fill_count(&self) -> Option<usize> {
+        let floor = self.floor_y()?;
+        let top = self.sources.iter().map(|source| source.1).min()?;
+        let mut reachable: HashSet<isize> = HashSet::new();
+        let mut count = 0;
+        for y in top..floor {
+            let mut row: HashSet<isize> = reachable
+                .iter()
+                .flat_map(|x| [x - 1, *x, x + 1])
+                .chain(
+                    self.sources
+                        .iter()
+                        .filter(|source| source.1 == y)
+                        .map(|source| source.0),
+                )
+                .collect();
+            row.retain(|&x| !self.rocks.contains(&(x, y)));
+            count += row.len();
+            reachable = row;
+        }
+        Some(count)
+    }
+
+    // Bounds of everything worth drawing, including the floor below it all
+    pub fn bounds(&self) -> Bounds {
+        Bounds {
+            minx: min(self.rock_bounds.minx, self.sand_bounds.minx),
+            maxx: max(self.rock_bounds.maxx, self.sand_bounds.maxx),
+            miny: min(min(self.rock_bounds.miny, self.sand_bounds.miny), 0),
+            maxy: self
+                .floor_y()
+                .unwrap_or(max(self.rock_bounds.maxy, self.sand_bounds.maxy)),
+        }
+    }
+
+    // The cave as a binary PPM image, one pixel per cell
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let bounds = self.bounds();
+        let width = bounds.maxx - bounds.minx + 1;
+        let height = bounds.maxy - bounds.miny + 1;
+        let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for y in bounds.miny..=bounds.maxy {
+            for x in bounds.minx..=bounds.maxx {
+                let color: [u8; 3] = if self.sources.contains(&(x, y)) {
+                    [220, 40, 40]
+                } else {
+                    match self.position_occupied_by((x, y)) {
+                        Occupation::Rock => [110, 110, 110],
+                        Occupation::Sand => [230, 190, 90],
+                        Occupation::Air => [20, 20, 30],
+                    }
+                };
+                image.extend(color);
+            }
+        }
+        image
+    }
+
+    pub fn print(&self) {
+        let Bounds {
+            minx,
+            maxx,
+            miny,
+            maxy,
+        } = self.bounds();
         println!(
             "{}",
             (0..3)
@@ -167,7 +280,7 @@ impl Grid {
                 })
                 .join("\n")
         );
-        for y in min(miny, 0)..=maxy {
+        for y in miny..=maxy {
             print!("{:0>3} ", y);
             for x in minx..=maxx {
                 print!(
@@ -179,46 +292,88 @@ impl Grid {
                     }
                 );
             }
-            println!("");
+            println!();
         }
     }
 }
 
 static DEBUG: bool = false;
 
-fn part1(mut grid: Grid) -> usize {
+pub fn part1(mut grid: Grid) -> usize {
     if DEBUG {
         grid.print();
     }
-
-    while let Some(settled_pos) = grid.drop_sand((500, 0)) {
+    grid.simulate(|grid, settled_pos| {
         if DEBUG {
             grid.print();
             println!("Sand settled at ({}, {})", settled_pos.0, settled_pos.1);
         }
-    }
-    grid.sands.len()
+    })
 }
 
-fn part2(mut grid: Grid) -> usize {
-    grid.set_pt2(true);
-    if DEBUG {
-        grid.print();
-    }
-    let start_pos: Point = (500, 0);
-    while grid.drop_sand(start_pos) != Some(start_pos) {
-        if DEBUG {
-            grid.print();
-        }
-    }
-    grid.sands.len()
+pub fn part2(mut grid: Grid) -> usize {
+    grid.set_bottom(Bottom::Floor { depth: 2 });
+    grid.fill_count().expect("The cave has a floor")
 }
 
-fn main() {
-    let lines: Vec<String> = io::stdin().lines().filter_map(Result::ok).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let grid = Grid::parse(&lines);
+    fn grid(lines: &[&str]) -> Grid {
+        Grid::parse(
+            &lines
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
 
-    println!("Part 1: {}", part1(grid.clone()));
-    println!("Part 2: {}", part2(grid));
+    #[test]
+    fn example() {
+        let example: Vec<&str> = include_str!("example.txt").lines().collect();
+        assert_eq!(part1(grid(&example)), 24);
+        assert_eq!(part2(grid(&example)), 93);
+    }
+
+    #[test]
+    fn fill_count_matches_simulation() {
+        let example: Vec<&str> = include_str!("example.txt").lines().collect();
+        let caves = [
+            example,
+            vec!["495,5 -> 505,5", "498,3 -> 498,8 -> 502,8"],
+            // A cup under the source with a lid that sand has to go around
+            vec!["490,4 -> 490,9 -> 510,9 -> 510,4", "499,2 -> 501,2"],
+            vec!["500,3 -> 500,3"],
+            // A roof right under the source
+            vec!["497,1 -> 503,1", "494,6 -> 496,6", "505,3 -> 505,7"],
+        ];
+        let sources = [
+            vec![(500, 0)],
+            vec![(500, 0), (503, 2)],
+            vec![(492, 0), (508, 1), (500, 0)],
+            vec![(500, 0), (500, 0)],
+            // The second source is inside a rock in some caves
+            vec![(500, 0), (502, 5)],
+        ];
+        for cave in &caves {
+            for sources in &sources {
+                for depth in [1, 2, 5] {
+                    let mut grid = grid(cave);
+                    grid.sources = sources.to_vec();
+                    grid.set_bottom(Bottom::Floor { depth });
+                    assert_eq!(
+                        grid.fill_count(),
+                        Some(grid.clone().simulate(|_, _| ())),
+                        "{:?} with sources {:?} and a floor {} below",
+                        cave,
+                        sources,
+                        depth
+                    );
+                }
+            }
+        }
+        // Without a floor it's up to the simulation
+        assert_eq!(grid(&["500,3 -> 500,3"]).fill_count(), None);
+    }
 }
@@ -0,0 +1,61 @@
+use std::env;
+use std::fs;
+use std::io;
+
+use day14::{part1, part2, Bottom, Grid};
+
+fn main() {
+    let lines: Vec<String> = io::stdin().lines().map_while(Result::ok).collect();
+
+    let mut grid = Grid::parse(&lines);
+
+    // Optional `source=X,Y` (repeatable), `floor=DEPTH` and `image=PATH`
+    // arguments for trying out other caves. The image is of the cave once
+    // all the sand has settled, and other arguments are ignored.
+    let mut sources = Vec::new();
+    let mut floor = None;
+    let mut image_name = None;
+    for arg in env::args().skip(1) {
+        match arg.split_once('=') {
+            Some(("source", value)) => {
+                let (x, y) = value.split_once(',').expect("Source should be X,Y");
+                sources.push((
+                    x.parse().expect("Couldn't parse source x"),
+                    y.parse().expect("Couldn't parse source y"),
+                ));
+            }
+            Some(("floor", value)) => {
+                floor = Some(value.parse().expect("Couldn't parse floor depth"))
+            }
+            Some(("image", value)) => image_name = Some(value.to_string()),
+            _ => (),
+        }
+    }
+    if !sources.is_empty() {
+        grid.sources = sources;
+    }
+
+    if let Some(image_name) = image_name {
+        let mut settled = grid.clone();
+        if let Some(depth) = floor {
+            settled.set_bottom(Bottom::Floor { depth });
+        }
+        settled.simulate(|_, _| ());
+        fs::write(image_name, settled.to_ppm()).expect("Couldn't write image");
+    }
+
+    match floor {
+        None => {
+            println!("Part 1: {}", part1(grid.clone()));
+            println!("Part 2: {}", part2(grid));
+        }
+        Some(depth) => {
+            grid.set_bottom(Bottom::Floor { depth });
+            println!(
+                "Sand with a floor {} below: {}",
+                depth,
+                grid.fill_count().expect("The cave has a floor")
+            );
+        }
+    }
+}
@@ -1,26 +1,23 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::cmp::{max, min};
+use std::cmp::max;
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
-struct Point {
-    x: isize,
-    y: isize,
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
-struct Sensor {
-    position: Point,
-    beacon: Point,
-    range: isize,
+pub struct Sensor {
+    pub position: Point,
+    pub beacon: Point,
+    pub range: isize,
 }
 
 impl Sensor {
-    fn new(position: Point, beacon: Point) -> Sensor {
+    pub fn new(position: Point, beacon: Point) -> Sensor {
         Sensor {
             position,
             beacon,
@@ -29,13 +26,54 @@ impl Sensor {
     }
 }
 
-fn parse(lines: &Vec<String>) -> Vec<Sensor> {
+// The row and search area, which are much smaller in the example. The real
+// input's values are the default, and `params=example` switches to the
+// example's before any other values are set.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Params {
+    // Row to count the positions that can't contain a beacon on
+    pub row: isize,
+    // Largest x and y the distress beacon can be at
+    pub max_coord: isize,
+}
+
+impl Params {
+    pub const EXAMPLE: Params = Params {
+        row: 10,
+        max_coord: 20,
+    };
+    pub const INPUT: Params = Params {
+        row: 2000000,
+        max_coord: 4000000,
+    };
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        if name == "params" {
+            *self = match value {
+                "example" => Params::EXAMPLE,
+                "input" => Params::INPUT,
+                _ => panic!("Unknown parameter set {}", value),
+            };
+            return;
+        }
+        let value = value
+            .parse::<isize>()
+            .unwrap_or_else(|_| panic!("Couldn't parse {} as int for {}", value, name));
+        match name {
+            "row" => self.row = value,
+            "max_coord" => self.max_coord = value,
+            _ => panic!("Unknown parameter {}", name),
+        }
+    }
+}
+
+pub fn parse(lines: &[String]) -> Vec<Sensor> {
     static SENSOR_RE: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"Sensor at x=(\-?\d+), y=(\-?\d+): closest beacon is at x=(\-?\d+), y=(\-?\d+)")
             .unwrap()
     });
     lines
-        .into_iter()
+        .iter()
         .filter_map(|line| -> Option<Sensor> {
             let parsed_line = SENSOR_RE
                 .captures_iter(line)
@@ -55,115 +93,276 @@ fn parse(lines: &Vec<String>) -> Vec<Sensor> {
         .collect()
 }
 
-fn manhattan(p1: Point, p2: Point) -> isize {
+pub fn manhattan(p1: Point, p2: Point) -> isize {
     isize::abs(p2.x - p1.x) + isize::abs(p2.y - p1.y)
 }
 
-fn part1(sensors: &Vec<Sensor>, y: isize) -> usize {
-    let beacon_positions: HashSet<isize> = sensors
-        .iter()
-        .filter(|sensor| sensor.beacon.y == y)
-        .map(|sensor| sensor.beacon.x)
-        .collect();
-    let sensor_positions: HashSet<isize> = sensors
-        .iter()
-        .filter(|sensor| sensor.position.y == y)
-        .map(|sensor| sensor.position.x)
-        .collect();
+// Manhattan geometry. Rotating by 45 degrees with u = x + y and v = x - y
+// turns every sensor's diamond into an axis aligned square, and the lines
+// along its edges into lines of constant u or v.
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Square {
+    pub u: (isize, isize),
+    pub v: (isize, isize),
+}
+
+impl Square {
+    pub fn contains(&self, p: Point) -> bool {
+        let (u, v) = (p.x + p.y, p.x - p.y);
+        (self.u.0..=self.u.1).contains(&u) && (self.v.0..=self.v.1).contains(&v)
+    }
+}
+
+impl Sensor {
+    pub fn square(&self) -> Square {
+        let (u, v) = (
+            self.position.x + self.position.y,
+            self.position.x - self.position.y,
+        );
+        Square {
+            u: (u - self.range, u + self.range),
+            v: (v - self.range, v + self.range),
+        }
+    }
 
-    sensors
-        .into_iter()
-        .map(|sensor| {
-            let horizontal_range_at_y = sensor.range - isize::abs(sensor.position.y - y);
-            (sensor.position.x - horizontal_range_at_y)
-                ..=(sensor.position.x + horizontal_range_at_y)
+    // Covered x values on a row, if the diamond reaches it
+    pub fn row_span(&self, y: isize) -> Option<(isize, isize)> {
+        let horizontal_range_at_y = self.range - isize::abs(self.position.y - y);
+        (horizontal_range_at_y >= 0).then_some((
+            self.position.x - horizontal_range_at_y,
+            self.position.x + horizontal_range_at_y,
+        ))
+    }
+}
+
+// Sorted, non-overlapping and non-touching inclusive intervals
+pub fn union(mut spans: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
+    spans.sort();
+    let mut merged: Vec<(isize, isize)> = Vec::new();
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = max(last.1, end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+pub fn covered_on_row(sensors: &[Sensor], y: isize) -> Vec<(isize, isize)> {
+    union(
+        sensors
+            .iter()
+            .filter_map(|sensor| sensor.row_span(y))
+            .collect(),
+    )
+}
+
+// The line a * x + b * y = c
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+struct Line {
+    a: isize,
+    b: isize,
+    c: isize,
+}
+
+impl Line {
+    fn intersection(&self, other: &Line) -> Option<Point> {
+        let det = self.a * other.b - self.b * other.a;
+        if det == 0 {
+            return None;
+        }
+        let x = self.c * other.b - self.b * other.c;
+        let y = self.a * other.c - self.c * other.a;
+        (x % det == 0 && y % det == 0).then_some(Point {
+            x: x / det,
+            y: y / det,
         })
-        .fold(HashSet::<isize>::new(), |mut s1, s2| {
-            s1.extend(s2);
-            s1
+    }
+}
+
+// Uncovered cells in 0..=max_coord on both axes. A lone uncovered cell has
+// covered cells or the region's edge on every side, and a cell just outside a
+// square is on one of the lines one past its edges, so the cell sits on at
+// least one of those. If they all run the same way, the cell is in a one cell
+// wide diagonal channel between squares, which has to be closed off on both
+// sides of the cell. As u and v always have the same parity as each other, a
+// square closing it off can end up to two past the cell. Checking where the
+// lines one and two past every edge cross each other and the region's edges
+// therefore finds every lone cell, along with at least the ends of any larger
+// uncovered area.
+pub fn uncovered_cells(sensors: &[Sensor], max_coord: isize) -> Vec<Point> {
+    let squares: Vec<Square> = sensors.iter().map(Sensor::square).collect();
+    let mut lines: Vec<Line> = squares
+        .iter()
+        .flat_map(|square| {
+            [1, 2].into_iter().flat_map(move |offset| {
+                [
+                    Line {
+                        a: 1,
+                        b: 1,
+                        c: square.u.0 - offset,
+                    },
+                    Line {
+                        a: 1,
+                        b: 1,
+                        c: square.u.1 + offset,
+                    },
+                    Line {
+                        a: 1,
+                        b: -1,
+                        c: square.v.0 - offset,
+                    },
+                    Line {
+                        a: 1,
+                        b: -1,
+                        c: square.v.1 + offset,
+                    },
+                ]
+            })
         })
-        .difference(&beacon_positions)
-        .copied()
-        .collect::<HashSet<_>>()
-        .difference(&sensor_positions)
-        .copied()
-        .collect::<HashSet<_>>()
-        .len()
-}
-
-fn part2_ranges(sensors: &Vec<Sensor>, max_coord: isize) -> isize {
-    let minx: isize = 0;
-    let maxx: isize = max_coord;
-
-    let beacon_point = (0..=max_coord)
-        .find_map(|y| {
-            type Range = (isize, isize);
-            let mut x_ranges = sensors
+        .chain([
+            Line { a: 1, b: 0, c: 0 },
+            Line {
+                a: 1,
+                b: 0,
+                c: max_coord,
+            },
+            Line { a: 0, b: 1, c: 0 },
+            Line {
+                a: 0,
+                b: 1,
+                c: max_coord,
+            },
+        ])
+        .collect();
+    lines.sort_by_key(|line| (line.a, line.b, line.c));
+    lines.dedup();
+
+    let region = 0..=max_coord;
+    let mut cells: Vec<Point> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            lines[i + 1..]
                 .iter()
-                .filter_map(|sensor| {
-                    let horizontal_range_at_y = sensor.range - isize::abs(sensor.position.y - y);
-                    if horizontal_range_at_y > 0 {
-                        Some((
-                            min(max(sensor.position.x - horizontal_range_at_y, minx), maxx),
-                            max(min(sensor.position.x + horizontal_range_at_y, maxx), minx),
-                        ))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            x_ranges.sort_by(|r1, r2| isize::cmp(&r1.0, &r2.0));
-            let reduced_range = x_ranges
-                .into_iter()
-                .reduce(|r1, r2| {
-                    if r1.1 < r2.0 {
-                        r1
-                    } else {
-                        (min(r1.0, r2.0), max(r1.1, r2.1))
-                    }
-                })
-                .expect("Couldn't get reduced range");
-
-            if reduced_range.1 != maxx {
-                let x = reduced_range.1 + 1;
-                Some(Point { x, y })
-            } else if reduced_range.0 != minx {
-                let x = reduced_range.0 - 1;
-                Some(Point { x, y })
-            } else {
-                None
-            }
+                .filter_map(move |other| line.intersection(other))
         })
-        .expect("Couldn't find beacon point");
+        .filter(|p| region.contains(&p.x) && region.contains(&p.y))
+        .filter(|&p| squares.iter().all(|square| !square.contains(p)))
+        .collect();
+    cells.sort_by_key(|p| (p.x, p.y));
+    cells.dedup();
+    cells
+}
 
-    println!(
-        "Possible sensor location: {},{}",
-        beacon_point.x, beacon_point.y
-    );
-    beacon_point.x * 4000000 + beacon_point.y
+pub fn part1(sensors: &[Sensor], y: isize) -> usize {
+    let covered = covered_on_row(sensors, y);
+    // Beacons already on the row are left out of the count
+    let beacons: HashSet<isize> = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.y == y)
+        .map(|beacon| beacon.x)
+        .collect();
+    let covered_len: isize = covered.iter().map(|(start, end)| end - start + 1).sum();
+    covered_len as usize
+        - beacons
+            .iter()
+            .filter(|x| covered.iter().any(|(start, end)| (start..=end).contains(x)))
+            .count()
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 4 {
-        return println!("Usage: {} [path/to/input_file] [pt1 y] [pt2 y]", args[0]);
+// The distress beacon's tuning frequency, or every place it could be if
+// that isn't exactly one
+pub fn part2(sensors: &[Sensor], max_coord: isize) -> Result<isize, Vec<Point>> {
+    match uncovered_cells(sensors, max_coord)[..] {
+        [cell] => Ok(cell.x * 4000000 + cell.y),
+        ref cells => Err(cells.to_vec()),
     }
-    let input_name: &String = &args[1];
-    let pt1_y: isize = args[2]
-        .parse::<isize>()
-        .expect("Couldn't parse second argument as int");
-    let pt2_y: isize = args[3]
-        .parse::<isize>()
-        .expect("Couldn't parse third argument as int");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let parsed = parse(&lines);
+    // Every cell in the region that no sensor covers, by checking them all
+    fn brute_force(sensors: &[Sensor], max_coord: isize) -> Vec<Point> {
+        (0..=max_coord)
+            .flat_map(|x| (0..=max_coord).map(move |y| Point { x, y }))
+            .filter(|&p| {
+                sensors
+                    .iter()
+                    .all(|sensor| manhattan(sensor.position, p) > sensor.range)
+            })
+            .collect()
+    }
+
+    fn example() -> Vec<Sensor> {
+        let lines: Vec<String> = include_str!("example.txt")
+            .lines()
+            .map(str::to_string)
+            .collect();
+        parse(&lines)
+    }
 
-    println!("Part 1: {}", part1(&parsed, pt1_y));
-    println!("Part 2: {}", part2_ranges(&parsed, pt2_y));
+    #[test]
+    fn example_answers() {
+        let params = Params::EXAMPLE;
+        assert_eq!(part1(&example(), params.row), 26);
+        assert_eq!(part2(&example(), params.max_coord), Ok(56000011));
+    }
+
+    #[test]
+    fn part2_reports_every_candidate() {
+        // Without the sensor at 8,7 a whole area is left uncovered
+        let sensors: Vec<Sensor> = example()
+            .into_iter()
+            .filter(|sensor| sensor.position != Point { x: 8, y: 7 })
+            .collect();
+        let candidates = part2(&sensors, Params::EXAMPLE.max_coord).unwrap_err();
+        assert!(candidates.len() > 1);
+        assert!(candidates.contains(&Point { x: 14, y: 11 }));
+        assert_eq!(part2(&[], 1).unwrap_err().len(), 4);
+    }
+
+    #[test]
+    fn uncovered_cells_matches_brute_force() {
+        static MAX_COORD: isize = 12;
+        // Where sensors go relative to the cell they're placed to cover. There
+        // are an odd number so every offset is used with both ranges below.
+        static OFFSETS: [(isize, isize); 7] =
+            [(0, 0), (2, -1), (-3, 2), (1, 3), (-2, -3), (3, 1), (-1, -2)];
+        let mut k = 0;
+        for hidden in brute_force(&[], MAX_COORD) {
+            // Puts sensors near covered cells, each reaching just short of
+            // the hidden cell, until that's the only one left uncovered
+            let mut sensors: Vec<Sensor> = Vec::new();
+            while let Some(&target) = brute_force(&sensors, MAX_COORD)
+                .iter()
+                .find(|&&p| p != hidden)
+            {
+                let (dx, dy) = OFFSETS[k % OFFSETS.len()];
+                let position = Point {
+                    x: target.x + dx,
+                    y: target.y + dy,
+                };
+                let range = manhattan(position, hidden) - 1 - (k % 2) as isize;
+                k += 1;
+                if manhattan(position, target) <= range {
+                    let beacon = Point {
+                        x: position.x + range,
+                        y: position.y,
+                    };
+                    sensors.push(Sensor::new(position, beacon));
+                }
+            }
+            assert_eq!(
+                uncovered_cells(&sensors, MAX_COORD),
+                vec![hidden],
+                "{:?}",
+                sensors
+            );
+        }
+    }
 }
@@ -0,0 +1,43 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::process;
+
+use day15::{parse, part1, part2, Params};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        return println!(
+            "Usage: {} [path/to/input_file] [params=example|input] [row=N] [max_coord=N]",
+            args[0]
+        );
+    }
+    let input_name: &str = &args[1];
+    let mut params = Params::INPUT;
+    for arg in &args[2..] {
+        let (name, value) = arg
+            .split_once('=')
+            .expect("Couldn't parse parameter as name=value");
+        params.set(name, value);
+    }
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let parsed = parse(&lines);
+
+    println!("Part 1: {}", part1(&parsed, params.row));
+    match part2(&parsed, params.max_coord) {
+        Ok(frequency) => println!("Part 2: {}", frequency),
+        Err(candidates) => {
+            eprintln!(
+                "Part 2 failed: expected one place for the distress beacon, found {:?}",
+                candidates
+            );
+            process::exit(1);
+        }
+    }
+}
@@ -1,26 +1,23 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::cmp::min;
-use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::collections::HashMap;
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
-struct Valve {
-    name: String,
-    flow_rate: usize,
-    tunnels: Vec<String>,
+pub struct Valve {
+    pub name: String,
+    pub flow_rate: usize,
+    pub tunnels: Vec<String>,
 }
 
-fn parse(lines: &Vec<String>) -> (Vec<Valve>, HashMap<String, usize>) {
+pub fn parse(lines: &[String]) -> (Vec<Valve>, HashMap<String, usize>) {
     static VALVE_RE: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"Valve ([A-Z]+) has flow rate=(\d+); tunnels? leads? to valves? ([A-Z, ]+)")
             .unwrap()
     });
 
     let valves = lines
-        .into_iter()
+        .iter()
         .map(|line| -> Valve {
             let parsed_line = VALVE_RE
                 .captures_iter(line)
@@ -47,111 +44,174 @@ fn parse(lines: &Vec<String>) -> (Vec<Valve>, HashMap<String, usize>) {
     (valves, names)
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
-struct State {
-    open_valves: HashSet<usize>,
-    total_flow: usize,
-    current_valves: Vec<usize>,
-}
-
-impl State {
-    fn new(start_valve: usize, num_agents: usize) -> State {
-        State {
-            open_valves: [].into_iter().collect(),
-            total_flow: 0,
-            current_valves: vec![start_valve; num_agents],
+// Shortest number of minutes to walk between every pair of valves
+pub fn distances(valves: &[Valve], names: &HashMap<String, usize>) -> Vec<Vec<usize>> {
+    let mut dists = vec![vec![usize::MAX / 2; valves.len()]; valves.len()];
+    for (i, valve) in valves.iter().enumerate() {
+        dists[i][i] = 0;
+        for tunnel in &valve.tunnels {
+            dists[i][names[tunnel]] = 1;
         }
     }
+    for k in 0..valves.len() {
+        for i in 0..valves.len() {
+            for j in 0..valves.len() {
+                dists[i][j] = min(dists[i][j], dists[i][k] + dists[k][j]);
+            }
+        }
+    }
+    dists
 }
 
-fn simulate_valves(
-    valves: &Vec<Valve>,
-    names: &HashMap<String, usize>,
-    time: usize,
-    num_agents: usize,
-    max_state_pool_size: usize,
-) -> usize {
-    let start_room = names["AA"];
-    let mut states = vec![State::new(start_room, num_agents)];
-    for minute in 1..=time {
-        println!("== Minute {} ==", minute);
-
-        for mut state in states.iter_mut() {
-            state.total_flow += state
-                .open_valves
-                .iter()
-                .map(|open_valve| valves[*open_valve].flow_rate)
-                .sum::<usize>();
-        }
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct Opening {
+    pub valve: String,
+    // Minute spent opening the valve, it releases pressure from the next one on
+    pub minute: usize,
+}
 
-        for i_agent in 0..num_agents {
-            let mut new_states = Vec::<State>::new();
-            for state in states.iter_mut() {
-                let new_state = state.clone();
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Plan {
+    pub total_pressure: usize,
+    pub schedules: Vec<Vec<Opening>>,
+}
 
-                let current_valve_index = &mut state.current_valves[i_agent];
-                let current_valve = &valves[*current_valve_index];
+// Valves opened by one agent and the minute it opens each of them
+type Route = Vec<(usize, usize)>;
 
-                let reuse_state_offset = if !state.open_valves.contains(&current_valve_index)
-                    && current_valve.flow_rate > 0
-                {
-                    state.open_valves.insert(current_valve_index.clone());
-                    0
-                } else {
-                    let tunnel = current_valve
-                        .tunnels
-                        .iter()
-                        .take(1)
-                        .next()
-                        .expect("Couldn't get first tunnel");
-                    *current_valve_index = names[tunnel];
-                    1
-                };
+// The graph reduced to the valves worth opening, which are the only places
+// worth walking to
+struct Compressed<'a> {
+    valves: &'a [Valve],
+    useful: Vec<usize>,
+    dists: Vec<Vec<usize>>,
+    time: usize,
+    // Best pressure a single agent can release opening exactly the valves
+    // in each bitmask of useful, along with the order it opens them in
+    best: Vec<Option<(usize, Route)>>,
+}
 
-                new_states.extend(current_valve.tunnels.iter().skip(reuse_state_offset).map(
-                    |tunnel| {
-                        let mut new_state = new_state.clone();
-                        new_state.current_valves[i_agent] = names[tunnel];
-                        new_state
-                    },
-                ));
+impl Compressed<'_> {
+    fn search(
+        &mut self,
+        pos: usize,
+        time_left: usize,
+        open: usize,
+        pressure: usize,
+        path: &mut Route,
+    ) {
+        if self.best[open]
+            .as_ref()
+            .is_none_or(|(best_pressure, _)| pressure > *best_pressure)
+        {
+            self.best[open] = Some((pressure, path.clone()));
+        }
+        for i in 0..self.useful.len() {
+            if open & (1 << i) != 0 {
+                continue;
+            }
+            let valve = self.useful[i];
+            let cost = self.dists[pos][valve] + 1;
+            if cost >= time_left {
+                continue;
             }
-            states.extend(new_states);
+            let remaining = time_left - cost;
+            path.push((valve, self.time - remaining));
+            self.search(
+                valve,
+                remaining,
+                open | (1 << i),
+                pressure + remaining * self.valves[valve].flow_rate,
+                path,
+            );
+            path.pop();
         }
-        states.sort_by(|s1, s2| usize::cmp(&s2.total_flow, &s1.total_flow));
-        states.resize(
-            min(max_state_pool_size, states.len()),
-            State::new(start_room, num_agents),
-        );
     }
-    states
-        .iter()
-        .map(|state| state.total_flow)
-        .max()
-        .expect("Couldn't get max flow")
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
+pub fn plan_valves(
+    valves: &[Valve],
+    names: &HashMap<String, usize>,
+    time: usize,
+    num_agents: usize,
+) -> Plan {
+    let useful = (0..valves.len())
+        .filter(|i| valves[*i].flow_rate > 0)
+        .collect::<Vec<_>>();
+    let num_sets = 1 << useful.len();
+    let mut compressed = Compressed {
+        valves,
+        useful,
+        dists: distances(valves, names),
+        time,
+        best: vec![None; num_sets],
+    };
+    compressed.search(names["AA"], time, 0, 0, &mut vec![]);
+    let best = compressed.best;
+
+    // combined[k][set] is the most pressure k + 1 agents can release opening
+    // exactly set between them, with the set the last agent opened
+    let mut combined = vec![best
+        .iter()
+        .map(|b| b.as_ref().map(|(pressure, _)| (*pressure, 0)))
+        .collect::<Vec<_>>()];
+    for _ in 1..num_agents {
+        let prev = combined.last().expect("Couldn't get previous agent count");
+        let next = (0..num_sets)
+            .map(|set| {
+                let mut choice: Option<(usize, usize)> = None;
+                let mut own = set;
+                loop {
+                    if let (Some((own_pressure, _)), Some((rest_pressure, _))) =
+                        (&best[own], prev[set ^ own])
+                    {
+                        let pressure = own_pressure + rest_pressure;
+                        if choice.is_none_or(|(best_pressure, _)| pressure > best_pressure) {
+                            choice = Some((pressure, own));
+                        }
+                    }
+                    if own == 0 {
+                        break;
+                    }
+                    own = (own - 1) & set;
+                }
+                choice
+            })
+            .collect::<Vec<_>>();
+        combined.push(next);
     }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
 
-    let (valves, names) = parse(&lines);
+    let all = combined.last().expect("Need at least one agent");
+    let (mut set, (total_pressure, _)) = all
+        .iter()
+        .enumerate()
+        .filter_map(|(set, c)| c.map(|c| (set, c)))
+        .max_by_key(|(_, (pressure, _))| *pressure)
+        .expect("Couldn't find any plan");
 
-    println!("valves: {:?} names: {:?}", valves, names);
+    let mut schedules = vec![];
+    for agent_sets in combined.iter().rev() {
+        let (_, own) = agent_sets[set].expect("Couldn't follow plan back");
+        let own = if schedules.len() + 1 == num_agents {
+            set
+        } else {
+            own
+        };
+        let (_, path) = best[own].as_ref().expect("Couldn't get agent's path");
+        schedules.push(
+            path.iter()
+                .map(|(valve, minute)| Opening {
+                    valve: valves[*valve].name.clone(),
+                    minute: *minute,
+                })
+                .collect(),
+        );
+        set ^= own;
+    }
+    schedules.reverse();
 
-    println!("Part 1: {}", simulate_valves(&valves, &names, 30, 1, 2048));
-    println!(
-        "Part 2: {}",
-        simulate_valves(&valves, &names, 26, 2, (2 as usize).pow(16))
-    );
+    Plan {
+        total_pressure,
+        schedules,
+    }
 }
@@ -0,0 +1,44 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day16::{parse, plan_valves, Plan};
+
+fn print_plan(plan: &Plan) {
+    for (i, schedule) in plan.schedules.iter().enumerate() {
+        eprintln!(
+            "Agent {}: {}",
+            i + 1,
+            schedule
+                .iter()
+                .map(|opening| format!("{}@{}", opening.valve, opening.minute))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+}
+
+fn main() {
+    if env::args().count() != 2 {
+        return println!(
+            "Usage: {} [path/to/input_file]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let (valves, names) = parse(&lines);
+
+    let plan = plan_valves(&valves, &names, 30, 1);
+    print_plan(&plan);
+    println!("Part 1: {}", plan.total_pressure);
+
+    let plan = plan_valves(&valves, &names, 26, 2);
+    print_plan(&plan);
+    println!("Part 2: {}", plan.total_pressure);
+}
@@ -1,14 +1,11 @@
 use std::cmp::max;
-use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::collections::HashMap;
 use std::thread;
 use std::time::{self, Instant};
 
-type RockShape = Vec<Vec<bool>>;
+pub type RockShape = Vec<Vec<bool>>;
 
-fn parse_rock_shapes(lines: &Vec<String>) -> Vec<RockShape> {
+pub fn parse_rock_shapes(lines: &[String]) -> Vec<RockShape> {
     lines
         .split(String::is_empty)
         .map(|rock_lines| {
@@ -22,342 +19,374 @@ fn parse_rock_shapes(lines: &Vec<String>) -> Vec<RockShape> {
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
-fn parse_jet_input(line: &String) -> Vec<Direction> {
-    line.chars()
+pub fn parse_jet_input(line: &str) -> Vec<Direction> {
+    let jets: Vec<Direction> = line
+        .chars()
         .filter_map(|c| match c {
             '>' => Some(Direction::Right),
             '<' => Some(Direction::Left),
             _ => None,
         })
-        .collect()
-}
-
-// Grid is reversed, the last items are the highest
-type Grid = Vec<Vec<bool>>;
-
-fn print_grid(grid: &Grid) {
-    grid.iter().rev().for_each(|row| {
-        println!(
-            "|{}|",
-            row.into_iter()
-                .map(|b| if *b { '#' } else { '.' })
-                .collect::<String>()
-        );
-    });
+        .collect();
+    assert!(!jets.is_empty(), "Couldn't find any jets in {:?}", line);
+    jets
 }
 
-static GRID_WIDTH: usize = 7;
-
 static ANIMATION_SPEED: time::Duration = time::Duration::from_millis(200);
 static ANIMATE: bool = false;
-static VERBOSE: bool = false;
-static DEBUG: bool = false;
+pub static VERBOSE: bool = false;
+pub static DEBUG: bool = false;
 
-fn print_grid_with_rock(grid: &Grid, current_rock: &RockShape, bottom_y: usize, left_x: usize) {
-    let rock_height = current_rock.len();
-    let rock_width = current_rock[0].len();
+pub const DEFAULT_WIDTH: usize = 7;
 
-    let maxy = max(grid.len(), bottom_y + current_rock.len());
-    if ANIMATE {
-        thread::sleep(ANIMATION_SPEED);
-        println!("\x1Bc");
-    }
-    for y in (0..maxy).rev() {
-        print!("|");
-        for x in 0..GRID_WIDTH {
-            let rock_range_y = bottom_y..bottom_y + rock_height;
-            let rock_range_x = left_x..left_x + rock_width;
-            if (0..grid.len()).contains(&y) && grid[y][x] {
-                print!("#");
-            } else if rock_range_y.contains(&y)
-                && rock_range_x.contains(&x)
-                && current_rock[y - bottom_y][x - left_x]
-            {
-                print!("@");
-            } else {
-                print!(".");
-            }
+// A rock as one bitmask per row, bottom row first, with bit x set when
+// column x from its left edge is filled
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rock {
+    pub rows: Vec<u64>,
+    pub width: usize,
+}
+
+impl Rock {
+    pub fn from_shape(shape: &RockShape) -> Rock {
+        Rock {
+            rows: shape
+                .iter()
+                .rev()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, filled)| **filled)
+                        .fold(0, |bits, (x, _)| bits | 1 << x)
+                })
+                .collect(),
+            width: shape.iter().map(Vec::len).max().unwrap_or(0),
         }
-        println!("|");
     }
-    println!("+-------+");
-    println!("");
 }
 
-fn simulate_rocks(
-    rock_sequence: &Vec<RockShape>,
-    jet_sequence: &Vec<Direction>,
-    num_rocks: usize,
-) -> usize {
-    let mut grid: Grid = vec![];
-    let mut tick_count = 0;
-    let mut current_jet_in_sequence = 0;
-
-    let mut cleared_row_offset: usize = 0;
-
-    let start = Instant::now();
+// The rock currently falling, by its shape and bottom left corner
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FallingRock {
+    pub shape: usize,
+    pub x: usize,
+    pub y: usize,
+}
 
-    let mut repeat_lengths = HashSet::<usize>::new();
+// The next rock and jet, and the top rows of the tower from the top down
+pub type Fingerprint = (usize, usize, Vec<u64>);
+
+pub struct Chamber {
+    pub width: usize,
+    // Settled rocks as one bitmask per row, from the floor up
+    pub rows: Vec<u64>,
+    pub rocks: Vec<Rock>,
+    pub jets: Vec<Direction>,
+    pub next_rock: usize,
+    pub next_jet: usize,
+    pub settled_count: usize,
+    pub falling: Option<FallingRock>,
+}
 
-    //static REPEAT_START_ROCK: usize = 7200; // example
-    static REPEAT_START_ROCK: usize = 398200; // input
+impl Chamber {
+    pub fn new(width: usize, shapes: &[RockShape], jets: &[Direction]) -> Chamber {
+        assert!((1..=64).contains(&width), "Chamber width has to be 1 to 64");
+        let rocks: Vec<Rock> = shapes.iter().map(Rock::from_shape).collect();
+        // Rocks appear two columns from the left wall
+        assert!(
+            rocks.iter().all(|rock| rock.width + 2 <= width),
+            "Rock too wide to appear in the chamber"
+        );
+        assert!(!jets.is_empty(), "Need at least one jet");
+        Chamber {
+            width,
+            rows: Vec::new(),
+            rocks,
+            jets: jets.to_vec(),
+            next_rock: 0,
+            next_jet: 0,
+            settled_count: 0,
+            falling: None,
+        }
+    }
 
-    //static REPEAT_LENGTH: usize = 200; // for searching
-    //static REPEAT_LENGTH: usize = 7000; // example
-    static REPEAT_LENGTH: usize = 745200 - REPEAT_START_ROCK; // input
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
 
-    let mut repeat_start_height = 0;
-    let mut repeat_end_height = 0;
+    fn collides(&self, shape: usize, x: usize, y: usize) -> bool {
+        let rock = &self.rocks[shape];
+        x + rock.width > self.width
+            || rock
+                .rows
+                .iter()
+                .enumerate()
+                .any(|(i, bits)| self.rows.get(y + i).is_some_and(|row| row & bits << x != 0))
+    }
 
-    for i_rock in 0..=num_rocks {
-        let current_rock_in_sequence = i_rock % rock_sequence.len();
+    // Puts the next rock two columns from the left wall and three rows
+    // above the tower
+    pub fn spawn(&mut self) {
+        assert!(self.falling.is_none(), "A rock is already falling");
+        self.falling = Some(FallingRock {
+            shape: self.next_rock,
+            x: 2,
+            y: self.height() + 3,
+        });
+        self.next_rock = (self.next_rock + 1) % self.rocks.len();
+    }
 
-        let highest_y = cleared_row_offset + grid.len() as usize;
+    // Moves the falling rock with the next jet, returning whether it moved
+    pub fn push(&mut self) -> bool {
+        let mut rock = self.falling.expect("No rock is falling");
+        let jet = self.jets[self.next_jet];
+        self.next_jet = (self.next_jet + 1) % self.jets.len();
+        let x = match jet {
+            Direction::Left if rock.x > 0 => rock.x - 1,
+            Direction::Right => rock.x + 1,
+            _ => return false,
+        };
+        if self.collides(rock.shape, x, rock.y) {
+            return false;
+        }
+        rock.x = x;
+        self.falling = Some(rock);
+        true
+    }
 
-        let current_rock = &rock_sequence[current_rock_in_sequence];
+    // Moves the falling rock down a row, returning whether it could
+    pub fn fall(&mut self) -> bool {
+        let mut rock = self.falling.expect("No rock is falling");
+        if rock.y == 0 || self.collides(rock.shape, rock.x, rock.y - 1) {
+            return false;
+        }
+        rock.y -= 1;
+        self.falling = Some(rock);
+        true
+    }
 
-        let mut bottom_y = highest_y + 3;
-        let mut left_x = 2;
+    // Turns the falling rock into part of the tower, returning where it
+    // came to rest
+    pub fn settle(&mut self) -> FallingRock {
+        let rock = self.falling.take().expect("No rock is falling");
+        let shape = &self.rocks[rock.shape];
+        let top = rock.y + shape.rows.len();
+        if self.rows.len() < top {
+            self.rows.resize(top, 0);
+        }
+        for (i, bits) in shape.rows.iter().enumerate() {
+            self.rows[rock.y + i] |= bits << rock.x;
+        }
+        self.settled_count += 1;
+        rock
+    }
 
+    // Spawns the next rock and lets it fall until it comes to rest, returning
+    // where it did
+    pub fn drop_rock(&mut self) -> FallingRock {
+        self.spawn();
         if VERBOSE {
             println!("A new rock begins falling:");
-            print_grid_with_rock(
-                &grid,
-                &current_rock,
-                (bottom_y - cleared_row_offset) as usize,
-                left_x,
-            );
+            self.print();
         }
-
         loop {
-            let jet_dir = jet_sequence[current_jet_in_sequence];
-            let rock_blocked_at_coord = |left_x: usize, bottom_y: usize| -> bool {
-                current_rock
-                    .iter()
-                    .rev() // grid y is reversed
-                    .enumerate()
-                    .any(|(y, row)| {
-                        row.iter().enumerate().any(|(x, b)| {
-                            *b && grid.len() as usize + cleared_row_offset > y as usize + bottom_y
-                                && grid[0].len() > x + left_x
-                                && grid[(y as usize + bottom_y - cleared_row_offset) as usize]
-                                    [x + left_x]
-                        })
-                    })
-            };
-            match jet_dir {
-                Direction::Right => {
-                    let rock_width = current_rock[0].len();
-                    let right_x = left_x + rock_width - 1;
-                    // TODO: Add check for existing rocks
-                    if right_x + 1 < GRID_WIDTH && !rock_blocked_at_coord(left_x + 1, bottom_y) {
-                        if VERBOSE {
-                            println!("Jet of gas pushes rock right:");
-                        }
-                        left_x += 1;
-                    } else {
-                        if VERBOSE {
-                            println!("Jet of gas pushes rock right, but nothing happens:");
-                        }
-                    }
-                }
-                Direction::Left => {
-                    // TODO: Add check for existing rocks
-                    if left_x > 0 && !rock_blocked_at_coord(left_x - 1, bottom_y) {
-                        if VERBOSE {
-                            println!("Jet of gas pushes rock left:");
-                        }
-                        left_x -= 1;
-                    } else {
-                        if VERBOSE {
-                            println!("Jet of gas pushes rock left, but nothing happens:");
-                        }
-                    }
-                }
-            }
-
+            self.push();
             if VERBOSE {
-                print_grid_with_rock(
-                    &grid,
-                    &current_rock,
-                    (bottom_y - cleared_row_offset) as usize,
-                    left_x,
-                );
+                println!("Jet of gas pushes rock:");
+                self.print();
             }
-
-            tick_count += 1;
-            current_jet_in_sequence = tick_count % jet_sequence.len();
-
-            if bottom_y == 0 || rock_blocked_at_coord(left_x, bottom_y - 1) {
-                let rock_height = current_rock.len();
-                grid.resize(
-                    max(
-                        rock_height + (bottom_y - cleared_row_offset) as usize,
-                        grid.len(),
-                    ),
-                    vec![false; GRID_WIDTH],
-                );
-                current_rock
-                    .iter()
-                    .rev() // grid is reversed in y
-                    .enumerate()
-                    .for_each(|(y, row)| {
-                        row.iter().enumerate().for_each(|(x, b)| {
-                            if *b {
-                                let grid_pos = &mut grid
-                                    [y + (bottom_y - cleared_row_offset) as usize][x + left_x];
-                                *grid_pos = *b;
-                            }
-                        })
-                    });
+            if !self.fall() {
+                let rock = self.settle();
                 if VERBOSE {
                     println!("Rock falls 1 unit, causing it to come to rest:");
-                    print_grid_with_rock(
-                        &grid,
-                        &current_rock,
-                        (bottom_y - cleared_row_offset) as usize,
-                        left_x,
-                    );
-                }
-                break;
-            } else {
-                if VERBOSE {
-                    println!("Rock falls 1 unit:");
+                    self.print();
                 }
-                bottom_y -= 1;
-            }
-
-            if VERBOSE {
-                print_grid_with_rock(
-                    &grid,
-                    &current_rock,
-                    (bottom_y - cleared_row_offset) as usize,
-                    left_x,
-                );
+                return rock;
             }
         }
+    }
 
-        if grid.len() > 1024 {
-            let full_row = grid.len()
-                - 10
-                - (0..GRID_WIDTH)
-                    .into_iter()
-                    .map(|x| {
-                        grid.iter()
-                            .rev()
-                            .position(|row| row[x])
-                            .expect("Couldn't find filled position in column")
-                    })
-                    .max()
-                    .expect("Couldn't get highest filled position");
+    // The top `depth` rows of the tower from the top down, with the floor
+    // as full rows below the bottom one
+    pub fn top_rows(&self, depth: usize) -> Vec<u64> {
+        let floor = u64::MAX >> (64 - self.width);
+        (1..=depth)
+            .map(|below_top| {
+                self.height()
+                    .checked_sub(below_top)
+                    .map_or(floor, |y| self.rows[y])
+            })
+            .collect()
+    }
+
+    pub fn fingerprint(&self, depth: usize) -> Fingerprint {
+        (self.next_rock, self.next_jet, self.top_rows(depth))
+    }
 
-            grid = grid[full_row..].to_vec();
-            cleared_row_offset += full_row as usize;
+    pub fn print(&self) {
+        if ANIMATE {
+            thread::sleep(ANIMATION_SPEED);
+            println!("\x1Bc");
         }
+        let falling = self.falling.map(|rock| (rock, &self.rocks[rock.shape]));
+        let top = falling.map_or(self.height(), |(rock, shape)| {
+            max(self.height(), rock.y + shape.rows.len())
+        });
+        for y in (0..top).rev() {
+            let settled = self.rows.get(y).copied().unwrap_or(0);
+            let moving = falling
+                .and_then(|(rock, shape)| {
+                    y.checked_sub(rock.y)
+                        .and_then(|i| shape.rows.get(i))
+                        .map(|bits| bits << rock.x)
+                })
+                .unwrap_or(0);
+            println!(
+                "|{}|",
+                (0..self.width)
+                    .map(|x| if settled & 1 << x != 0 {
+                        '#'
+                    } else if moving & 1 << x != 0 {
+                        '@'
+                    } else {
+                        '.'
+                    })
+                    .collect::<String>()
+            );
+        }
+        println!("+{}+", "-".repeat(self.width));
+        println!();
+    }
+}
 
+// Height of the tower after num_rocks have settled. A rock only depends on
+// the rows it gets down to, counting the one below it that stops it. The
+// fingerprint has as many rows as the deepest any rock has got so far, so
+// if it repeats with none of the rocks since it was first seen getting any
+// deeper, the ones after it will settle the same way again, and whole
+// repetitions are skipped by adding the height each one adds. A tower that
+// never repeats like that, say with rocks falling ever further down a wide
+// open side, is simulated rock by rock.
+pub fn simulate_rocks(
+    rock_sequence: &[RockShape],
+    jet_sequence: &[Direction],
+    width: usize,
+    num_rocks: usize,
+) -> usize {
+    let mut chamber = Chamber::new(width, rock_sequence, jet_sequence);
+    // Rocks settled and tower height when each fingerprint was last seen,
+    // since a rock last got deeper than before
+    let mut seen = HashMap::<Fingerprint, (usize, usize)>::new();
+    let mut depth = 0;
+    let mut skipped_height = 0;
+
+    let start = Instant::now();
+    while chamber.settled_count < num_rocks {
+        let top = chamber.height();
+        let rock = chamber.drop_rock();
         if DEBUG {
-            println!("Grid after {} rocks:", i_rock + 1);
-            print_grid(&grid);
+            println!("Chamber after {} rocks:", chamber.settled_count);
+            chamber.print();
         }
-        if num_rocks > REPEAT_START_ROCK {
-            let position_in_sequence = (num_rocks - REPEAT_START_ROCK) % REPEAT_LENGTH;
-            if i_rock == REPEAT_START_ROCK {
-                repeat_start_height = grid.len() as usize + cleared_row_offset;
-            } else if i_rock == REPEAT_START_ROCK + REPEAT_LENGTH {
-                repeat_end_height = grid.len() as usize + cleared_row_offset;
-            } else if i_rock == REPEAT_START_ROCK + REPEAT_LENGTH + position_in_sequence {
-                break;
-            }
-            if i_rock > REPEAT_START_ROCK && (i_rock - REPEAT_START_ROCK) % REPEAT_LENGTH == 0 {
-                let rps = i_rock as f64 / start.elapsed().as_secs_f64();
-                if rps > 0.0 {
-                    let rocks_remaining = num_rocks - i_rock;
-                    let time_remaining_s: usize = rocks_remaining / rps as usize;
-                    let time_remaining_m: usize = time_remaining_s / 60;
-                    let time_remaining_h: f64 = time_remaining_m as f64 / 60.0;
-                    let time_remaining_d: f64 = time_remaining_h as f64 / 24.0;
-                    let avg_repeat_length =
-                        (grid.len() as usize + cleared_row_offset) % REPEAT_LENGTH;
-                    if repeat_lengths.contains(&avg_repeat_length) {
-                        println!(
-                            "Rock num: {} ({:.2} rocks/s), {}s/{}m/{:.2}h/{:.2}d remaining; height: {}",
-                            i_rock,
-                            rps,
-                            time_remaining_s,
-                            time_remaining_m,
-                            time_remaining_h,
-                            time_remaining_d,
-                            avg_repeat_length);
-                    } else {
-                        repeat_lengths.insert(avg_repeat_length);
-                    }
-                }
+        if skipped_height > 0 {
+            continue;
+        }
+        if top + 1 - rock.y > depth {
+            depth = top + 1 - rock.y;
+            seen.clear();
+        }
+        let fingerprint = chamber.fingerprint(depth);
+        let state = (chamber.settled_count, chamber.height());
+        if let Some((repeat_start_rock, repeat_start_height)) = seen.insert(fingerprint, state) {
+            let repeat_length = chamber.settled_count - repeat_start_rock;
+            let repeat_height = chamber.height() - repeat_start_height;
+            let num_repeats = (num_rocks - chamber.settled_count) / repeat_length;
+            if VERBOSE {
+                println!(
+                    "Rocks {} to {} repeat, adding {} rows each time",
+                    repeat_start_rock, chamber.settled_count, repeat_height
+                );
             }
+            // Counting the skipped rocks as settled keeps the remainder
+            // going from the same point in the repetition
+            chamber.settled_count += num_repeats * repeat_length;
+            skipped_height = num_repeats * repeat_height;
         }
     }
-
-    let position_in_sequence_height = grid.len() as usize + cleared_row_offset - repeat_end_height;
-    let repeat_height = repeat_end_height - repeat_start_height;
-    if num_rocks > REPEAT_START_ROCK + REPEAT_LENGTH {
-        let position_in_sequence = (num_rocks - REPEAT_START_ROCK) % REPEAT_LENGTH;
-        let num_repeats = (num_rocks - position_in_sequence - REPEAT_START_ROCK) / REPEAT_LENGTH;
-        assert_eq!(
-            num_rocks,
-            position_in_sequence + REPEAT_START_ROCK + num_repeats * REPEAT_LENGTH
+    if VERBOSE {
+        println!(
+            "Simulated {} rocks in {:.2}s",
+            chamber.settled_count,
+            start.elapsed().as_secs_f64()
         );
-        position_in_sequence_height + repeat_end_height + repeat_height * (num_repeats - 1) - 1
-    } else {
-        grid.len() as usize + cleared_row_offset
     }
+    chamber.height() + skipped_height
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        return println!(
-            "Usage: {} [jet file] [rocks file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let jet_input_name: &String = &args[1];
-    let jet_input_f = File::open(jet_input_name).expect("Couldn't open input file");
-    let jet_input: String = io::BufReader::new(jet_input_f)
-        .lines()
-        .next()
-        .expect("Couldn't read input line")
-        .expect("Couldn't read input line")
-        .trim()
-        .to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let rocks_name: &String = &args[2];
-    let rocks_f = File::open(rocks_name).expect("Couldn't open input file");
-    let rocks_lines: Vec<String> = io::BufReader::new(rocks_f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
+    fn shapes() -> Vec<RockShape> {
+        let lines: Vec<String> = include_str!("rockshapes.txt")
+            .lines()
+            .map(str::to_string)
+            .collect();
+        parse_rock_shapes(&lines)
+    }
 
-    let rock_sequence = parse_rock_shapes(&rocks_lines);
-    let jet_sequence = parse_jet_input(&jet_input);
+    // Drops every rock without looking for repetitions
+    fn plain_height(shapes: &[RockShape], jets: &[Direction], width: usize, rocks: usize) -> usize {
+        let mut chamber = Chamber::new(width, shapes, jets);
+        for _ in 0..rocks {
+            chamber.drop_rock();
+        }
+        chamber.height()
+    }
 
-    if DEBUG || VERBOSE {
-        println!(
-            "Part 1: {}",
-            simulate_rocks(&rock_sequence, &jet_sequence, 20)
-        );
-    } else {
-        println!(
-            "Part 1: {}",
-            simulate_rocks(&rock_sequence, &jet_sequence, 2022)
+    #[test]
+    fn example() {
+        let jets = parse_jet_input(include_str!("example.txt"));
+        assert_eq!(simulate_rocks(&shapes(), &jets, 7, 2022), 3068);
+        assert_eq!(
+            simulate_rocks(&shapes(), &jets, 7, 1_000_000_000_000),
+            1514285714288
         );
     }
 
-    println!(
-        "Part 2: {}",
-        simulate_rocks(&rock_sequence, &jet_sequence, 1_000_000_000_000)
-    );
+    #[test]
+    fn simulate_rocks_matches_plain_simulation() {
+        let shapes = shapes();
+        let patterns = [
+            include_str!("example.txt").trim(),
+            "<",
+            ">",
+            "<>",
+            "<<>",
+            ">>><",
+            "<><<>>><<<<>",
+            "><<<>>>>><<>><>>",
+            ">>>>>>>>>>>>>>>><<<<<<<<<<<<<<<<<",
+        ];
+        for pattern in patterns {
+            let jets = parse_jet_input(pattern);
+            for width in [6, 7, 9, 12, 16, 20, 33, 64] {
+                for rocks in [1, 5, 100, 2022, 3043] {
+                    assert_eq!(
+                        simulate_rocks(&shapes, &jets, width, rocks),
+                        plain_height(&shapes, &jets, width, rocks),
+                        "{} rocks in a chamber {} wide with jets {}",
+                        rocks,
+                        width,
+                        pattern
+                    );
+                }
+            }
+        }
+    }
 }
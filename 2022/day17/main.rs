@@ -0,0 +1,61 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day17::{parse_jet_input, parse_rock_shapes, simulate_rocks, DEBUG, DEFAULT_WIDTH, VERBOSE};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        return println!(
+            "Usage: {} [jet file] [rocks file] [width=N]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let jet_input_name: &str = &args[1];
+    let mut width = DEFAULT_WIDTH;
+    for arg in &args[3..] {
+        let (name, value) = arg
+            .split_once('=')
+            .expect("Couldn't parse parameter as name=value");
+        match name {
+            "width" => width = value.parse().expect("Couldn't parse chamber width"),
+            _ => panic!("Unknown parameter {}", name),
+        }
+    }
+    let jet_input_f = File::open(jet_input_name).expect("Couldn't open input file");
+    let jet_input: String = io::BufReader::new(jet_input_f)
+        .lines()
+        .next()
+        .expect("Couldn't read input line")
+        .expect("Couldn't read input line")
+        .trim()
+        .to_string();
+
+    let rocks_name: &str = &args[2];
+    let rocks_f = File::open(rocks_name).expect("Couldn't open input file");
+    let rocks_lines: Vec<String> = io::BufReader::new(rocks_f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let rock_sequence = parse_rock_shapes(&rocks_lines);
+    let jet_sequence = parse_jet_input(&jet_input);
+
+    if DEBUG || VERBOSE {
+        println!(
+            "Part 1: {}",
+            simulate_rocks(&rock_sequence, &jet_sequence, width, 20)
+        );
+    } else {
+        println!(
+            "Part 1: {}",
+            simulate_rocks(&rock_sequence, &jet_sequence, width, 2022)
+        );
+    }
+
+    println!(
+        "Part 2: {}",
+        simulate_rocks(&rock_sequence, &jet_sequence, width, 1_000_000_000_000)
+    );
+}
@@ -1,11 +1,11 @@
 #!/bin/bash
 
 echo "EXAMPLE:"
-cat example.txt | cargo run
+cargo run --release example.txt rockshapes.txt
 
 if [[ -f input ]]; then
   echo
 
   echo "INPUT:"
-  cat input | cargo run
+  cargo run --release input rockshapes.txt
 fi
@@ -1,13 +1,9 @@
 use itertools::Itertools;
-use once_cell::sync::Lazy;
-use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::collections::{HashMap, HashSet};
 
-type Cube = (isize, isize, isize);
+pub type Cube = (isize, isize, isize);
 
-fn parse_cubes(lines: &Vec<String>) -> HashSet<Cube> {
+pub fn parse_cubes(lines: &[String]) -> HashSet<Cube> {
     lines
         .iter()
         .filter_map(|line| {
@@ -20,161 +16,241 @@ fn parse_cubes(lines: &Vec<String>) -> HashSet<Cube> {
         .collect()
 }
 
-static ADJACENT_COORDS: Lazy<Vec<Cube>> = Lazy::new(|| {
-    (-1..=1)
-        .into_iter()
-        .map(move |x| {
-            (-1..=1)
-                .into_iter()
-                .map(move |y| {
-                    (-1..=1)
-                        .into_iter()
-                        .filter_map(|z| {
-                            if [x, y, z].into_iter().filter(|a| *a == 0).count() == 2 {
-                                Some((x, y, z))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .flatten()
-        })
-        .flatten()
-        .collect()
-});
+// Offsets to the six cubes sharing a face, which are also the face normals
+pub static FACES: [Cube; 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
 
-fn add(c1: &Cube, c2: &Cube) -> Cube {
+pub fn add(c1: &Cube, c2: &Cube) -> Cube {
     (c1.0 + c2.0, c1.1 + c2.1, c1.2 + c2.2)
 }
 
-fn part1(cubes: &HashSet<Cube>) -> usize {
-    cubes
-        .iter()
-        .map(|cube| {
-            ADJACENT_COORDS
-                .iter()
-                .map(|coord| {
-                    let adjacent_cube = add(&cube, coord);
-                    if cubes.contains(&adjacent_cube) {
-                        0
-                    } else {
-                        1
-                    }
-                })
-                .sum::<usize>()
-        })
-        .sum::<usize>()
+// A dense grid of filled voxels over the bounding box of the cubes, padded
+// by one empty layer on every side so the outside is always connected
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VoxelGrid {
+    pub min: Cube,
+    pub max: Cube,
+    filled: Vec<bool>,
 }
 
-fn generate_cubes<F: FnMut(Cube) -> Option<Cube>>(bound: isize, mut condition: F) -> HashSet<Cube> {
-    (-bound..=bound)
-        .into_iter()
-        .filter_map(|x| {
-            (-bound..=bound)
-                .into_iter()
-                .map(|y| {
-                    (-bound..=bound)
-                        .into_iter()
-                        .filter_map(|z| {
-                            let cube = (x, y, z);
-
-                            condition(cube)
-                        })
-                        .collect::<HashSet<Cube>>()
-                })
-                .reduce(|mut hs1, hs2| {
-                    hs1.extend(&hs2);
-                    hs1
-                })
-        })
-        .reduce(|mut hs1, hs2| {
-            hs1.extend(&hs2);
-            hs1
+impl VoxelGrid {
+    pub fn new(cubes: &HashSet<Cube>) -> VoxelGrid {
+        let bound = |coord: fn(&Cube) -> isize| {
+            cubes
+                .iter()
+                .map(coord)
+                .minmax()
+                .into_option()
+                .map_or((0, 0), |(min, max)| (min - 1, max + 1))
+        };
+        let (x, y, z) = (bound(|c| c.0), bound(|c| c.1), bound(|c| c.2));
+        let mut grid = VoxelGrid {
+            min: (x.0, y.0, z.0),
+            max: (x.1, y.1, z.1),
+            filled: Vec::new(),
+        };
+        grid.filled = vec![false; grid.cells().count()];
+        for cube in cubes {
+            let i = grid.index(cube).expect("Cube outside its own bounds");
+            grid.filled[i] = true;
+        }
+        grid
+    }
+
+    fn index(&self, cube: &Cube) -> Option<usize> {
+        if !self.in_bounds(cube) {
+            return None;
+        }
+        let width = (self.max.0 - self.min.0 + 1) as usize;
+        let height = (self.max.1 - self.min.1 + 1) as usize;
+        let (x, y, z) = (
+            (cube.0 - self.min.0) as usize,
+            (cube.1 - self.min.1) as usize,
+            (cube.2 - self.min.2) as usize,
+        );
+        Some((z * height + y) * width + x)
+    }
+
+    pub fn in_bounds(&self, cube: &Cube) -> bool {
+        (self.min.0..=self.max.0).contains(&cube.0)
+            && (self.min.1..=self.max.1).contains(&cube.1)
+            && (self.min.2..=self.max.2).contains(&cube.2)
+    }
+
+    // Anything outside the bounds counts as empty
+    pub fn contains(&self, cube: &Cube) -> bool {
+        self.index(cube).is_some_and(|i| self.filled[i])
+    }
+
+    // Every position within the bounds, filled or not
+    pub fn cells(&self) -> impl Iterator<Item = Cube> + '_ {
+        (self.min.2..=self.max.2).flat_map(move |z| {
+            (self.min.1..=self.max.1)
+                .flat_map(move |y| (self.min.0..=self.max.0).map(move |x| (x, y, z)))
         })
-        .expect("Couldn't generate cube set")
-}
+    }
+
+    pub fn cubes(&self) -> impl Iterator<Item = Cube> + '_ {
+        self.cells().filter(|cube| self.contains(cube))
+    }
 
-fn part2(cubes: &HashSet<Cube>) -> usize {
-    let total_surface = part1(cubes);
+    pub fn neighbours(cube: &Cube) -> impl Iterator<Item = Cube> + '_ {
+        FACES.iter().map(move |face| add(cube, face))
+    }
 
-    let bound = cubes
-        .iter()
-        .map(|cube| [cube.0, cube.1, cube.2])
-        .flatten()
-        .map(isize::abs)
-        .max()
-        .expect("Couldn't get max bound");
-
-    let empty_cubes: HashSet<Cube> = generate_cubes(bound, |cube| {
-        if !cubes.contains(&cube) {
-            Some(cube)
-        } else {
-            None
+    // Every in-bounds cell reachable from start through face-adjacent cells
+    // that pass the check
+    pub fn flood_fill<F: Fn(&Cube) -> bool>(&self, start: Cube, passable: F) -> HashSet<Cube> {
+        let mut to_check = vec![start];
+        let mut reached = HashSet::from([start]);
+        while let Some(cube) = to_check.pop() {
+            for adjacent_cube in VoxelGrid::neighbours(&cube) {
+                if self.in_bounds(&adjacent_cube)
+                    && passable(&adjacent_cube)
+                    && reached.insert(adjacent_cube)
+                {
+                    to_check.push(adjacent_cube);
+                }
+            }
         }
-    });
+        reached
+    }
 
-    let boundary_cubes: HashSet<Cube> = generate_cubes(bound, |cube| {
-        if [cube.0, cube.1, cube.2]
-            .into_iter()
-            .map(isize::abs)
-            .filter(|a| *a == bound)
-            .count()
-            == 3
-            && !cubes.contains(&cube)
-        {
-            Some(cube)
-        } else {
-            None
-        }
-    });
-
-    let start_cube = boundary_cubes
-        .into_iter()
-        .next()
-        .expect("Couldn't get start cube");
-
-    assert!(empty_cubes.contains(&start_cube));
-
-    let mut to_check = [start_cube].into_iter().collect::<Vec<Cube>>();
-    let mut checked = HashSet::<Cube>::new();
-    while !to_check.is_empty() {
-        let cube = to_check.pop().expect("Couldn't get cube to check");
-        //dbg!(to_check.len());
-        //dbg!(checked.len());
-        checked.insert(cube);
-        to_check.extend(ADJACENT_COORDS.iter().filter_map(|coord| {
-            let adjacent_cube = add(&cube, coord);
-            if empty_cubes.contains(&adjacent_cube) && !checked.contains(&adjacent_cube) {
-                Some(adjacent_cube)
-            } else {
-                None
+    // Splits the cells that are filled (or empty) into face-connected groups
+    fn regions(&self, filled: bool) -> Vec<HashSet<Cube>> {
+        let mut seen = HashSet::new();
+        let mut regions = Vec::new();
+        for cube in self.cells() {
+            if self.contains(&cube) == filled && !seen.contains(&cube) {
+                let region = self.flood_fill(cube, |other| self.contains(other) == filled);
+                seen.extend(region.iter().copied());
+                regions.push(region);
             }
-        }));
+        }
+        regions
+    }
+
+    pub fn components(&self) -> Vec<HashSet<Cube>> {
+        self.regions(true)
+    }
+
+    // Empty cells that can be reached from outside the droplet
+    pub fn exterior(&self) -> HashSet<Cube> {
+        self.flood_fill(self.min, |cube| !self.contains(cube))
+    }
+
+    // Pockets of empty cells sealed off from the outside
+    pub fn cavities(&self) -> Vec<HashSet<Cube>> {
+        self.regions(false)
+            .into_iter()
+            .filter(|region| !region.contains(&self.min))
+            .collect()
+    }
+
+    // Each filled cube's faces that touch an empty cell, as (cube, normal)
+    pub fn exposed_faces(&self) -> Vec<(Cube, Cube)> {
+        self.cubes()
+            .flat_map(|cube| {
+                FACES
+                    .iter()
+                    .filter(move |face| !self.contains(&add(&cube, face)))
+                    .map(move |face| (cube, *face))
+            })
+            .collect()
     }
 
-    let checked_sides = part1(&checked);
-    let empty_sides = part1(&empty_cubes);
+    // Only the faces that touch the outside, leaving out cavity walls
+    pub fn exterior_faces(&self) -> Vec<(Cube, Cube)> {
+        let exterior = self.exterior();
+        self.exposed_faces()
+            .into_iter()
+            .filter(|(cube, face)| exterior.contains(&add(cube, face)))
+            .collect()
+    }
+
+    pub fn surface_area(&self) -> usize {
+        self.exposed_faces().len()
+    }
 
-    total_surface - (empty_sides - checked_sides)
+    pub fn exterior_surface_area(&self) -> usize {
+        self.exterior_faces().len()
+    }
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
+// The four corners of a unit cube's face, counter-clockwise when seen from
+// outside the cube
+fn face_corners(cube: &Cube, normal: &Cube) -> [Cube; 4] {
+    let base = [cube.0, cube.1, cube.2];
+    let normal = [normal.0, normal.1, normal.2];
+    let axis = normal
+        .iter()
+        .position(|n| *n != 0)
+        .expect("Normal has to point along an axis");
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let mut corners = [(0, 0), (1, 0), (1, 1), (0, 1)];
+    if normal[axis] < 0 {
+        corners.reverse();
     }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
+    corners.map(|(du, dv)| {
+        let mut corner = base;
+        corner[axis] += (normal[axis] > 0) as isize;
+        corner[u] += du;
+        corner[v] += dv;
+        (corner[0], corner[1], corner[2])
+    })
+}
+
+// A Wavefront OBJ mesh with one quad per face and shared vertices
+pub fn to_obj(faces: &[(Cube, Cube)]) -> String {
+    let mut vertices: HashMap<Cube, usize> = HashMap::new();
+    let mut vertex_lines = String::new();
+    let mut face_lines = String::new();
+    for (cube, normal) in faces {
+        let indices = face_corners(cube, normal).map(|corner| {
+            let next = vertices.len() + 1;
+            *vertices.entry(corner).or_insert_with(|| {
+                vertex_lines.push_str(&format!("v {} {} {}\n", corner.0, corner.1, corner.2));
+                next
+            })
+        });
+        face_lines.push_str(&format!("f {}\n", indices.iter().join(" ")));
+    }
+    vertex_lines + &face_lines
+}
 
-    let cubes = parse_cubes(&lines);
+// An ASCII STL mesh with two triangles per face
+pub fn to_stl(faces: &[(Cube, Cube)]) -> String {
+    let mut out = "solid droplet\n".to_string();
+    for (cube, normal) in faces {
+        let corners = face_corners(cube, normal);
+        for triangle in [[0, 1, 2], [0, 2, 3]] {
+            out.push_str(&format!(
+                "  facet normal {} {} {}\n    outer loop\n",
+                normal.0, normal.1, normal.2
+            ));
+            for i in triangle {
+                let corner = corners[i];
+                out.push_str(&format!(
+                    "      vertex {} {} {}\n",
+                    corner.0, corner.1, corner.2
+                ));
+            }
+            out.push_str("    endloop\n  endfacet\n");
+        }
+    }
+    out + "endsolid droplet\n"
+}
+
+pub fn part1(grid: &VoxelGrid) -> usize {
+    grid.surface_area()
+}
 
-    println!("Part 1: {}", part1(&cubes));
-    println!("Part 2: {}", part2(&cubes));
+pub fn part2(grid: &VoxelGrid) -> usize {
+    grid.exterior_surface_area()
 }
@@ -0,0 +1,37 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead};
+
+use day18::{parse_cubes, part1, part2, to_obj, to_stl, VoxelGrid};
+
+fn main() {
+    if !(2..=3).contains(&env::args().count()) {
+        return println!(
+            "Usage: {} [path/to/input_file] [droplet.obj|droplet.stl]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let cubes = parse_cubes(&lines);
+    let grid = VoxelGrid::new(&cubes);
+
+    // Optionally write out the outer surface to look at in a viewer
+    if let Some(mesh_name) = env::args().nth(2) {
+        let faces = grid.exterior_faces();
+        let mesh = if mesh_name.ends_with(".stl") {
+            to_stl(&faces)
+        } else {
+            to_obj(&faces)
+        };
+        fs::write(&mesh_name, mesh).expect("Couldn't write mesh file");
+    }
+
+    println!("Part 1: {}", part1(&grid));
+    println!("Part 2: {}", part2(&grid));
+}
@@ -1,48 +1,40 @@
-use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::cmp::{min, Reverse};
-use std::convert::identity;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::cmp::max;
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct ObsidianCost {
-    ore: usize,
-    clay: usize,
+pub struct ObsidianCost {
+    pub ore: usize,
+    pub clay: usize,
 }
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct GeodeCost {
-    ore: usize,
-    obsidian: usize,
+pub struct GeodeCost {
+    pub ore: usize,
+    pub obsidian: usize,
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct Blueprint {
-    id: usize,
-    ore_cost: usize,
-    clay_cost: usize,
-    obsidian_cost: ObsidianCost,
-    geode_cost: GeodeCost,
+pub struct Blueprint {
+    pub id: usize,
+    pub ore_cost: usize,
+    pub clay_cost: usize,
+    pub obsidian_cost: ObsidianCost,
+    pub geode_cost: GeodeCost,
 }
 
-fn parse(lines: &Vec<String>) -> Vec<Blueprint> {
-    fn parse_line(line: &String) -> Blueprint {
+pub fn parse(lines: &[String]) -> Vec<Blueprint> {
+    fn parse_line(line: &str) -> Blueprint {
         static LINE_RE: Lazy<Regex> = Lazy::new(|| {
             Regex::new([r"Blueprint (?P<id>\d+):",
                         r"Each ore robot costs (?P<ore>\d+) ore.",
                         r"Each clay robot costs (?P<clay>\d+) ore.",
                         r"Each obsidian robot costs (?P<obsidian_ore>\d+) ore and (?P<obsidian_clay>\d+) clay.",
                         r"Each geode robot costs (?P<geode_ore>\d+) ore and (?P<geode_obsidian>\d+) obsidian."]
-                        .iter()
-                        .cloned()
-                        .intersperse(r"\s+")
-                        .collect::<String>().as_str()
+                        .join(r"\s+").as_str()
                        ).unwrap()
         });
         let capts = LINE_RE
-            .captures(&line)
+            .captures(line)
             .expect("Couldn't parse line with regex");
         Blueprint {
             id: capts.name("id").unwrap().as_str().parse().unwrap(),
@@ -79,21 +71,40 @@ fn parse(lines: &Vec<String>) -> Vec<Blueprint> {
             .map(|lines| parse_line(&lines.join(" ")))
             .collect()
     } else {
-        lines.iter().map(parse_line).collect()
+        lines.iter().map(String::as_str).map(parse_line).collect()
     }
 }
 
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Robot {
+    Ore,
+    Clay,
+    Obsidian,
+    Geode,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Build {
+    pub robot: Robot,
+    // Minute the robot is started in, it collects from the one after
+    pub minute: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub geodes: usize,
+    pub builds: Vec<Build>,
+}
+
+// Geodes are counted as soon as a geode robot is built, for every minute it
+// has left to collect, so geode robots themselves never need tracking
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 struct State {
+    time_left: usize,
+
     ore_bots: usize,
     clay_bots: usize,
     obsidian_bots: usize,
-    geode_bots: usize,
-
-    ore_bots_in_progress: usize,
-    clay_bots_in_progress: usize,
-    obsidian_bots_in_progress: usize,
-    geode_bots_in_progress: usize,
 
     ore: usize,
     clay: usize,
@@ -102,160 +113,190 @@ struct State {
 }
 
 impl State {
-    fn new() -> State {
+    fn advance(&self, minutes: usize) -> State {
         State {
-            ore_bots: 1,
-            clay_bots: 0,
-            obsidian_bots: 0,
-            geode_bots: 0,
-
-            ore_bots_in_progress: 0,
-            clay_bots_in_progress: 0,
-            obsidian_bots_in_progress: 0,
-            geode_bots_in_progress: 0,
-
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
-            geodes: 0,
+            time_left: self.time_left - minutes,
+            ore: self.ore + self.ore_bots * minutes,
+            clay: self.clay + self.clay_bots * minutes,
+            obsidian: self.obsidian + self.obsidian_bots * minutes,
+            ..*self
         }
     }
 }
 
-fn run_blueprint(blueprint: &Blueprint, time: usize, max_state_pool_size: usize) -> usize {
-    let mut states = vec![State::new()];
-    for minute in 1..=time {
-        //println!("== Minute {} (Blueprint {}) == ", minute, blueprint.id);
-
-        let new_states = states
-            .iter()
-            .map(|state| {
-                [
-                    if state.ore >= blueprint.ore_cost {
-                        let mut new_state: State = *state;
-                        new_state.ore -= blueprint.ore_cost;
-                        new_state.ore_bots_in_progress += 1;
-                        Some(new_state)
-                    } else {
-                        None
-                    },
-                    if state.ore >= blueprint.clay_cost {
-                        let mut new_state: State = *state;
-                        new_state.ore -= blueprint.clay_cost;
-                        new_state.clay_bots_in_progress += 1;
-                        Some(new_state)
-                    } else {
-                        None
-                    },
-                    if state.ore >= blueprint.obsidian_cost.ore
-                        && state.clay >= blueprint.obsidian_cost.clay
-                    {
-                        let mut new_state: State = *state;
-                        new_state.ore -= blueprint.obsidian_cost.ore;
-                        new_state.clay -= blueprint.obsidian_cost.clay;
-                        new_state.obsidian_bots_in_progress += 1;
-                        Some(new_state)
-                    } else {
-                        None
-                    },
-                    if state.ore >= blueprint.geode_cost.ore
-                        && state.obsidian >= blueprint.geode_cost.obsidian
-                    {
-                        let mut new_state: State = *state;
-                        new_state.ore -= blueprint.geode_cost.ore;
-                        new_state.obsidian -= blueprint.geode_cost.obsidian;
-                        new_state.geode_bots_in_progress += 1;
-                        Some(new_state)
-                    } else {
-                        None
-                    },
-                ]
-                .into_iter()
-                .filter_map(identity)
-            })
-            .flatten()
-            .collect::<Vec<_>>();
-        states.extend(new_states);
-
-        for state in states.iter_mut() {
-            state.ore += state.ore_bots;
-            state.clay += state.clay_bots;
-            state.obsidian += state.obsidian_bots;
-            state.geodes += state.geode_bots;
+// Minutes of waiting until `have` reaches `cost` when collecting `rate` a
+// minute, or None if it never does
+fn minutes_until(cost: usize, have: usize, rate: usize) -> Option<usize> {
+    if have >= cost {
+        Some(0)
+    } else if rate == 0 {
+        None
+    } else {
+        Some((cost - have).div_ceil(rate))
+    }
+}
 
-            state.ore_bots += state.ore_bots_in_progress;
-            state.clay_bots += state.clay_bots_in_progress;
-            state.obsidian_bots += state.obsidian_bots_in_progress;
-            state.geode_bots += state.geode_bots_in_progress;
+struct Optimizer<'a> {
+    blueprint: &'a Blueprint,
+    time: usize,
+    // A robot can only be spent once a minute, so more collectors than the
+    // most expensive recipe needs are never useful
+    max_ore_bots: usize,
+    max_clay_bots: usize,
+    max_obsidian_bots: usize,
+    builds: Vec<Build>,
+    best: Plan,
+}
 
-            state.ore_bots_in_progress = 0;
-            state.clay_bots_in_progress = 0;
-            state.obsidian_bots_in_progress = 0;
-            state.geode_bots_in_progress = 0;
+impl Optimizer<'_> {
+    // Geodes that could still be reached if ore was free and every kind of
+    // robot could be built in the same minute
+    fn upper_bound(&self, state: &State) -> usize {
+        let blueprint = self.blueprint;
+        let (mut clay, mut obsidian, mut geodes) = (state.clay, state.obsidian, state.geodes);
+        let (mut clay_bots, mut obsidian_bots) = (state.clay_bots, state.obsidian_bots);
+        for time_left in (0..state.time_left).rev() {
+            let build_geode = obsidian >= blueprint.geode_cost.obsidian;
+            let build_obsidian = clay >= blueprint.obsidian_cost.clay;
+            clay += clay_bots;
+            obsidian += obsidian_bots;
+            if build_geode {
+                obsidian -= blueprint.geode_cost.obsidian;
+                geodes += time_left;
+            }
+            if build_obsidian {
+                clay -= blueprint.obsidian_cost.clay;
+                obsidian_bots += 1;
+            }
+            clay_bots += 1;
         }
+        geodes
+    }
 
-        states.sort_by_key(|state| {
-            Reverse((
-                state.geodes,
-                state.geode_bots,
-                state.geode_bots_in_progress,
-                state.obsidian,
-                state.obsidian_bots,
-                state.obsidian_bots_in_progress,
-                state.clay,
-                state.clay_bots,
-                state.clay_bots_in_progress,
-                state.ore,
-                state.ore_bots,
-                state.ore_bots_in_progress,
-            ))
-        });
-        states.resize(min(max_state_pool_size, states.len()), State::new());
+    // Saves up for the given robot and builds it, or None if it can't be
+    // finished with time left for it to collect anything
+    fn build(&self, state: &State, robot: Robot) -> Option<State> {
+        let blueprint = self.blueprint;
+        let wait = match robot {
+            Robot::Ore => minutes_until(blueprint.ore_cost, state.ore, state.ore_bots)?,
+            Robot::Clay => minutes_until(blueprint.clay_cost, state.ore, state.ore_bots)?,
+            Robot::Obsidian => max(
+                minutes_until(blueprint.obsidian_cost.ore, state.ore, state.ore_bots)?,
+                minutes_until(blueprint.obsidian_cost.clay, state.clay, state.clay_bots)?,
+            ),
+            Robot::Geode => max(
+                minutes_until(blueprint.geode_cost.ore, state.ore, state.ore_bots)?,
+                minutes_until(
+                    blueprint.geode_cost.obsidian,
+                    state.obsidian,
+                    state.obsidian_bots,
+                )?,
+            ),
+        };
+        if wait + 1 >= state.time_left {
+            return None;
+        }
+        let mut next = state.advance(wait + 1);
+        match robot {
+            Robot::Ore => {
+                next.ore -= blueprint.ore_cost;
+                next.ore_bots += 1;
+            }
+            Robot::Clay => {
+                next.ore -= blueprint.clay_cost;
+                next.clay_bots += 1;
+            }
+            Robot::Obsidian => {
+                next.ore -= blueprint.obsidian_cost.ore;
+                next.clay -= blueprint.obsidian_cost.clay;
+                next.obsidian_bots += 1;
+            }
+            Robot::Geode => {
+                next.ore -= blueprint.geode_cost.ore;
+                next.obsidian -= blueprint.geode_cost.obsidian;
+                next.geodes += next.time_left;
+            }
+        }
+        Some(next)
+    }
 
-        //println!("State 1: {:?}", states[0]);
+    fn search(&mut self, state: State) {
+        if state.geodes > self.best.geodes {
+            self.best = Plan {
+                geodes: state.geodes,
+                builds: self.builds.clone(),
+            };
+        }
+        if self.upper_bound(&state) <= self.best.geodes {
+            return;
+        }
+        // Try the most valuable robots first so good plans tighten the bound early
+        for robot in [Robot::Geode, Robot::Obsidian, Robot::Clay, Robot::Ore] {
+            let useful = match robot {
+                Robot::Ore => state.ore_bots < self.max_ore_bots,
+                Robot::Clay => state.clay_bots < self.max_clay_bots,
+                Robot::Obsidian => state.obsidian_bots < self.max_obsidian_bots,
+                Robot::Geode => true,
+            };
+            if !useful {
+                continue;
+            }
+            if let Some(next) = self.build(&state, robot) {
+                self.builds.push(Build {
+                    robot,
+                    minute: self.time - next.time_left,
+                });
+                self.search(next);
+                self.builds.pop();
+            }
+        }
     }
-    states
+}
+
+// Finds the most geodes the blueprint can open in the given time, by jumping
+// from one robot build to the next instead of simulating idle minutes
+pub fn optimize_blueprint(blueprint: &Blueprint, time: usize) -> Plan {
+    let mut optimizer = Optimizer {
+        blueprint,
+        time,
+        max_ore_bots: [
+            blueprint.ore_cost,
+            blueprint.clay_cost,
+            blueprint.obsidian_cost.ore,
+            blueprint.geode_cost.ore,
+        ]
         .into_iter()
-        .map(|state| state.geodes)
         .max()
-        .expect("Couldn't get largest geode count")
+        .unwrap(),
+        max_clay_bots: blueprint.obsidian_cost.clay,
+        max_obsidian_bots: blueprint.geode_cost.obsidian,
+        builds: Vec::new(),
+        best: Plan {
+            geodes: 0,
+            builds: Vec::new(),
+        },
+    };
+    optimizer.search(State {
+        time_left: time,
+        ore_bots: 1,
+        clay_bots: 0,
+        obsidian_bots: 0,
+        ore: 0,
+        clay: 0,
+        obsidian: 0,
+        geodes: 0,
+    });
+    optimizer.best
 }
 
-fn part1(blueprints: &Vec<Blueprint>) -> usize {
-    static MINUTES: usize = 24;
-    static POOL_SIZE: usize = (2 as usize).pow(8);
+pub fn part1(blueprints: &[Blueprint], plans: &[Plan]) -> usize {
     blueprints
         .iter()
-        .map(|blueprint| run_blueprint(&blueprint, MINUTES, POOL_SIZE) * blueprint.id)
+        .zip(plans)
+        .map(|(blueprint, plan)| blueprint.id * plan.geodes)
         .sum()
 }
 
-fn part2(blueprints: &Vec<Blueprint>) -> usize {
-    static MINUTES: usize = 32;
-    static POOL_SIZE: usize = (2 as usize).pow(16);
-    blueprints
-        .iter()
-        .take(3)
-        .map(|blueprint| run_blueprint(&blueprint, MINUTES, POOL_SIZE))
-        .product()
-}
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    let blueprints = parse(&lines);
-
-    println!("Part 1: {}", part1(&blueprints));
-    println!("Part 2: {}", part2(&blueprints));
+pub fn part2(plans: &[Plan]) -> usize {
+    plans.iter().map(|plan| plan.geodes).product()
 }
@@ -0,0 +1,52 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day19::{optimize_blueprint, parse, part1, part2, Blueprint, Plan};
+
+fn print_plans(blueprints: &[Blueprint], plans: &[Plan]) {
+    for (blueprint, plan) in blueprints.iter().zip(plans) {
+        eprintln!(
+            "Blueprint {} ({} geodes): {}",
+            blueprint.id,
+            plan.geodes,
+            plan.builds
+                .iter()
+                .map(|build| format!("{:?}@{}", build.robot, build.minute))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+}
+
+fn main() {
+    if env::args().count() != 2 {
+        return println!(
+            "Usage: {} [path/to/input_file]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let blueprints = parse(&lines);
+
+    let plans: Vec<Plan> = blueprints
+        .iter()
+        .map(|blueprint| optimize_blueprint(blueprint, 24))
+        .collect();
+    print_plans(&blueprints, &plans);
+    println!("Part 1: {}", part1(&blueprints, &plans));
+
+    let plans: Vec<Plan> = blueprints
+        .iter()
+        .take(3)
+        .map(|blueprint| optimize_blueprint(blueprint, 32))
+        .collect();
+    print_plans(&blueprints, &plans);
+    println!("Part 2: {}", part2(&plans));
+}
@@ -1,9 +1,6 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::ops::Range;
 
-fn parse(lines: &Vec<String>) -> Vec<isize> {
+pub fn parse(lines: &[String]) -> Vec<isize> {
     lines
         .iter()
         .map(|s| s.parse::<isize>().expect("couldn't parse int"))
@@ -11,13 +8,13 @@ fn parse(lines: &Vec<String>) -> Vec<isize> {
 }
 
 /*
-fn simple_wrapped_mod(mut i: isize, len: usize) -> usize {
+pub fn simple_wrapped_mod(mut i: isize, len: usize) -> usize {
     assert!(i >= 0);
     i as usize % len
 }
 */
 
-fn find_wrapped_index(old_i: isize, mut offset: isize, len: usize) -> usize {
+pub fn find_wrapped_index(old_i: isize, mut offset: isize, len: usize) -> usize {
     if offset < 0 {
         offset += (len as isize - 1) * (-offset / (len as isize - 1) + 1);
     }
@@ -29,11 +26,11 @@ fn find_wrapped_index(old_i: isize, mut offset: isize, len: usize) -> usize {
     ret
 }
 
-fn mix_numbers(fields: &Vec<isize>, indices: &Vec<usize>) -> (Vec<isize>, Vec<usize>) {
+pub fn mix_numbers(fields: &[isize], indices: &[usize]) -> (Vec<isize>, Vec<usize>) {
     let len = fields.len();
 
     let mixed = indices
-        .into_iter()
+        .iter()
         .fold(
             (
                 Vec::<(Range<usize>, isize)>::new(),
@@ -56,7 +53,7 @@ fn mix_numbers(fields: &Vec<isize>, indices: &Vec<usize>) -> (Vec<isize>, Vec<us
                 let insertion_offset: (Range<usize>, isize) = (real_i..len, 1);
                 let new_offsets = [removal_offset, insertion_offset];
                 new_offsets.iter().for_each(|new_offset| {
-                    ret.iter_mut().for_each(|mut field| {
+                    ret.iter_mut().for_each(|field| {
                         if new_offset.0.contains(&field.0) {
                             field.0 = (field.0 as isize + new_offset.1) as usize % len;
                             assert!((0..len).contains(&field.0));
@@ -77,11 +74,124 @@ fn mix_numbers(fields: &Vec<isize>, indices: &Vec<usize>) -> (Vec<isize>, Vec<us
         mixed.iter().map(|(i, _)| *i).collect(),
     )
 }
-fn mix_numbers_simple(fields: &Vec<isize>) -> (Vec<isize>, Vec<usize>) {
-    mix_numbers(fields, &(0..fields.len()).collect())
+// The original mixing, kept as a reference for checking `mix` against
+pub fn mix_numbers_simple(fields: &[isize]) -> (Vec<isize>, Vec<usize>) {
+    mix_numbers(fields, &(0..fields.len()).collect::<Vec<_>>())
+}
+
+// A circular sequence of the ids 0..len, split into buckets of about
+// sqrt(len) so that finding, removing and inserting an id are all O(sqrt n)
+pub struct CircularList {
+    buckets: Vec<Vec<usize>>,
+    // Which bucket each id is currently in
+    bucket_of: Vec<usize>,
+    bucket_size: usize,
+}
+
+impl CircularList {
+    pub fn new(len: usize) -> CircularList {
+        let mut list = CircularList {
+            buckets: vec![(0..len).collect()],
+            bucket_of: vec![0; len],
+            bucket_size: ((len as f64).sqrt() as usize).max(1),
+        };
+        list.rebuild();
+        list
+    }
+
+    pub fn len(&self) -> usize {
+        self.bucket_of.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bucket_of.is_empty()
+    }
+
+    // Evens the buckets out again once one has grown too big
+    fn rebuild(&mut self) {
+        let ids: Vec<usize> = self.buckets.drain(..).flatten().collect();
+        self.buckets = ids
+            .chunks(self.bucket_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        for (bucket, ids) in self.buckets.iter().enumerate() {
+            for &id in ids {
+                self.bucket_of[id] = bucket;
+            }
+        }
+    }
+
+    // Bucket and index within it of a position, with the position just past
+    // the end landing on the end of the last bucket
+    fn locate(&self, mut pos: usize) -> (usize, usize) {
+        for (bucket, ids) in self.buckets.iter().enumerate() {
+            if pos < ids.len() {
+                return (bucket, pos);
+            }
+            pos -= ids.len();
+        }
+        let last = self.buckets.len() - 1;
+        (last, self.buckets[last].len() + pos)
+    }
+
+    pub fn position(&self, id: usize) -> usize {
+        let bucket = self.bucket_of[id];
+        self.buckets[..bucket].iter().map(Vec::len).sum::<usize>()
+            + self.buckets[bucket]
+                .iter()
+                .position(|&other| other == id)
+                .expect("Id missing from its bucket")
+    }
+
+    pub fn get(&self, pos: usize) -> usize {
+        let (bucket, i) = self.locate(pos % self.len());
+        self.buckets[bucket][i]
+    }
+
+    // The id is still counted in len() until it's inserted again
+    pub fn remove(&mut self, pos: usize) -> usize {
+        let (bucket, i) = self.locate(pos);
+        self.buckets[bucket].remove(i)
+    }
+
+    pub fn insert(&mut self, pos: usize, id: usize) {
+        let (bucket, i) = self.locate(pos);
+        self.buckets[bucket].insert(i, id);
+        self.bucket_of[id] = bucket;
+        if self.buckets[bucket].len() > 2 * self.bucket_size {
+            self.rebuild();
+        }
+    }
+
+    // Moves an id `offset` places along, wrapping around the other len - 1
+    pub fn move_by(&mut self, id: usize, offset: isize) {
+        if self.len() < 2 {
+            return;
+        }
+        let pos = self.position(id);
+        self.remove(pos);
+        let new_pos = (pos as isize + offset).rem_euclid(self.len() as isize - 1) as usize;
+        self.insert(new_pos, id);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.buckets.iter().flatten().copied()
+    }
+}
+
+// Moves each number by its own value, in the original order, for every round
+pub fn mix(fields: &[isize], rounds: usize, decryption_key: isize) -> Vec<isize> {
+    let values: Vec<isize> = fields.iter().map(|x| x * decryption_key).collect();
+    let mut list = CircularList::new(values.len());
+    for _ in 0..rounds {
+        for (id, &value) in values.iter().enumerate() {
+            list.move_by(id, value);
+        }
+    }
+    list.iter().map(|id| values[id]).collect()
 }
 
-fn grove_coordinates(mixed: &Vec<isize>) -> isize {
+pub fn grove_coordinates(mixed: &[isize]) -> isize {
     let zero_pos = mixed
         .iter()
         .position(|i| *i == 0)
@@ -99,44 +209,75 @@ fn grove_coordinates(mixed: &Vec<isize>) -> isize {
         .sum::<isize>()
 }
 
-fn part1(fields: &Vec<isize>) -> isize {
-    let (mixed, _) = mix_numbers_simple(fields);
-    grove_coordinates(&mixed)
+pub fn part1(fields: &[isize]) -> isize {
+    grove_coordinates(&mix(fields, 1, 1))
 }
 
-fn part2(fields: &Vec<isize>) -> isize {
+pub fn part2(fields: &[isize]) -> isize {
     static DECRYPTION_KEY: isize = 811589153;
     static NUM_ITERATIONS: usize = 10;
-    let mut mixed = fields
-        .iter()
-        .map(|x| x * DECRYPTION_KEY)
-        .collect::<Vec<_>>();
-    //println!("Initial arrangement: \n{:?}", mixed);
-    let mut indices: Vec<usize> = (0..fields.len()).collect();
-    for iteration in 1..=NUM_ITERATIONS {
-        println!("Round {}", iteration);
-        (mixed, indices) = mix_numbers(&mixed, &indices);
-        //println!("After {} round of mixing: \n{:?}", iteration, mixed);
-    }
-    grove_coordinates(&mixed)
+    grove_coordinates(&mix(fields, NUM_ITERATIONS, DECRYPTION_KEY))
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rotates a mixed sequence to start at 0, as mixings only agree up to rotation
+    fn from_zero(mixed: &[isize]) -> Vec<isize> {
+        let zero_pos = mixed.iter().position(|&i| i == 0).unwrap();
+        mixed[zero_pos..]
+            .iter()
+            .chain(&mixed[..zero_pos])
+            .copied()
+            .collect()
     }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
 
-    let nums = parse(&lines);
+    // Mixes with the reference for several rounds, carrying on from where
+    // each original number ended up
+    fn reference_mix(fields: &[isize], rounds: usize, decryption_key: isize) -> Vec<isize> {
+        let mut values: Vec<isize> = fields.iter().map(|x| x * decryption_key).collect();
+        let mut positions: Vec<usize> = (0..fields.len()).collect();
+        for _ in 0..rounds {
+            (values, positions) = mix_numbers(&values, &positions);
+        }
+        values
+    }
 
-    println!("Part 1: {}", part1(&nums));
-    println!("Part 2: {}", part2(&nums));
+    #[test]
+    fn mix_matches_reference() {
+        let example = vec![1, 2, -3, 3, -2, 0, 4];
+        let short = [
+            vec![0],
+            vec![5, 0],
+            vec![3, -3, 0, 3],
+            vec![1, 1, 1, 0, -1, -1],
+            vec![7, -13, 0, 100, -100, 2, 2, 999, -1000, 6],
+            vec![-4, 12, -30, 4, 0, 8, 8, -8, 21, -1, 1000, 5, -6, 3, -997],
+        ];
+        // Longer, with a single 0, repeated values and moves around the list
+        // many times
+        let long: Vec<isize> = (0..150)
+            .map(|i| if i == 60 { 0 } else { i * i * 31 % 2001 - 1000 })
+            .collect();
+        for fields in [example, long].into_iter().chain(short) {
+            let (reference, _) = mix_numbers_simple(&fields);
+            assert_eq!(
+                from_zero(&mix(&fields, 1, 1)),
+                from_zero(&reference),
+                "{:?}",
+                fields
+            );
+            for (rounds, decryption_key) in [(3, 1), (1, 811589153), (10, 811589153)] {
+                assert_eq!(
+                    from_zero(&mix(&fields, rounds, decryption_key)),
+                    from_zero(&reference_mix(&fields, rounds, decryption_key)),
+                    "{:?} mixed {} times with key {}",
+                    fields,
+                    rounds,
+                    decryption_key
+                );
+            }
+        }
+    }
 }
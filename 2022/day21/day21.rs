@@ -1,19 +1,16 @@
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::fmt;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-enum Operation {
+pub enum Operation {
     Add,
     Subtract,
     Divide,
     Multiply,
-    Eq,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
-enum Monkey {
+pub enum Monkey {
     Operation {
         op: Operation,
         lhs: String,
@@ -23,7 +20,7 @@ enum Monkey {
 }
 
 #[derive(Debug)]
-enum ParseError {
+pub enum ParseError {
     ParseInt(std::num::ParseIntError),
     Other(String),
 }
@@ -42,11 +39,11 @@ impl From<&str> for ParseError {
         ParseError::Other(err.to_string())
     }
 }
-fn parse(lines: &Vec<String>) -> Result<HashMap<String, Monkey>, ParseError> {
+pub fn parse(lines: &[String]) -> Result<HashMap<String, Monkey>, ParseError> {
     lines
         .iter()
         .map(|line| {
-            let mut split_line = line.split(":").into_iter();
+            let mut split_line = line.split(":");
             let name = split_line
                 .next()
                 .ok_or("Couldn't get monkey name")?
@@ -55,7 +52,6 @@ fn parse(lines: &Vec<String>) -> Result<HashMap<String, Monkey>, ParseError> {
             let monkey_type_vals = split_line
                 .next()
                 .ok_or("Couldn't get monkey type/operation")?
-                .trim()
                 .split_whitespace()
                 .collect::<Vec<_>>();
 
@@ -82,25 +78,16 @@ fn parse(lines: &Vec<String>) -> Result<HashMap<String, Monkey>, ParseError> {
         .collect()
 }
 
-fn do_op(op: Operation, lhs: isize, rhs: isize) -> isize {
+pub fn do_op(op: Operation, lhs: isize, rhs: isize) -> isize {
     match op {
         Operation::Add => lhs + rhs,
         Operation::Subtract => lhs - rhs,
         Operation::Divide => lhs / rhs,
         Operation::Multiply => lhs * rhs,
-        Operation::Eq => {
-            if lhs < rhs {
-                -1
-            } else if lhs > rhs {
-                1
-            } else {
-                0
-            }
-        }
     }
 }
 
-fn process_monkeys(monkeys: &HashMap<String, Monkey>) -> HashMap<String, isize> {
+pub fn process_monkeys(monkeys: &HashMap<String, Monkey>) -> HashMap<String, isize> {
     let mut results: HashMap<String, isize> = monkeys
         .iter()
         .filter_map(|(name, monkey)| match monkey {
@@ -110,10 +97,7 @@ fn process_monkeys(monkeys: &HashMap<String, Monkey>) -> HashMap<String, isize>
         .collect();
 
     let mut to_process = vec!["root".to_string()];
-    while !to_process.is_empty() {
-        let curr = to_process
-            .pop()
-            .expect("Couldn't get top monkey to process");
+    while let Some(curr) = to_process.pop() {
         match &monkeys[curr.as_str()] {
             Monkey::Operation { op, rhs, lhs } => {
                 if let (Some(&lhs), Some(&rhs)) = (results.get(lhs), results.get(rhs)) {
@@ -135,68 +119,339 @@ fn process_monkeys(monkeys: &HashMap<String, Monkey>) -> HashMap<String, isize>
     results
 }
 
-fn part1(monkeys: &HashMap<String, Monkey>) -> isize {
-    let results = process_monkeys(&monkeys);
+pub fn part1(monkeys: &HashMap<String, Monkey>) -> isize {
+    let results = process_monkeys(monkeys);
 
     assert!(results.contains_key("root"));
 
     results["root"]
 }
 
-fn part2(mut monkeys: HashMap<String, Monkey>) -> isize {
-    if let Some(Monkey::Operation { op, lhs: _, rhs: _ }) = monkeys.get_mut("root") {
-        *op = Operation::Eq;
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MathError {
+    DivideByZero,
+    // A numerator or denominator doesn't fit in an i128
+    Overflow,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MathError::DivideByZero => write!(f, "Division by zero"),
+            MathError::Overflow => write!(f, "Overflow"),
+        }
     }
+}
 
-    static SEARCH_START: isize = 10isize.pow(14);
-    let mut lower_bound: isize = -SEARCH_START;
-    let mut upper_bound: isize = SEARCH_START;
-    for invert_cmp in [true, false] {
-        loop {
-            let test_val = lower_bound + (upper_bound - lower_bound) / 2;
-            if let Some(Monkey::Number(x)) = monkeys.get_mut("humn") {
-                *x = test_val;
+// Why humn couldn't be worked out
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SolveError {
+    UnknownMonkey(String),
+    // root yells a number instead of comparing two monkeys
+    RootIsNumber,
+    // Neither of root's sides depends on humn
+    NoHumn,
+    // humn is used more than once, so its operations can't just be undone
+    HumnRepeated,
+    // Folding a monkey that doesn't depend on humn into a constant failed
+    Monkey(String, MathError),
+    Math(MathError),
+}
+
+impl From<MathError> for SolveError {
+    fn from(err: MathError) -> Self {
+        SolveError::Math(err)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::UnknownMonkey(name) => write!(f, "There's no monkey {}", name),
+            SolveError::RootIsNumber => write!(f, "root should compare two monkeys"),
+            SolveError::NoHumn => write!(f, "humn isn't on either side of root"),
+            SolveError::HumnRepeated => write!(f, "humn is used more than once"),
+            SolveError::Monkey(name, err) => write!(f, "{} working out monkey {}", err, name),
+            SolveError::Math(err) => write!(f, "{} while undoing humn's operations", err),
+        }
+    }
+}
+
+// An exact fraction, always kept in lowest terms with a positive denominator
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    pub num: i128,
+    pub den: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Rational {
+        assert!(den != 0, "Rational with a zero denominator");
+        let divisor = gcd(num, den) * den.signum();
+        Rational {
+            num: num / divisor,
+            den: den / divisor,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn apply(op: Operation, lhs: Rational, rhs: Rational) -> Result<Rational, MathError> {
+        let mul = |a: i128, b: i128| a.checked_mul(b).ok_or(MathError::Overflow);
+        Ok(match op {
+            Operation::Add => Rational::new(
+                mul(lhs.num, rhs.den)?
+                    .checked_add(mul(rhs.num, lhs.den)?)
+                    .ok_or(MathError::Overflow)?,
+                mul(lhs.den, rhs.den)?,
+            ),
+            Operation::Subtract => Rational::new(
+                mul(lhs.num, rhs.den)?
+                    .checked_sub(mul(rhs.num, lhs.den)?)
+                    .ok_or(MathError::Overflow)?,
+                mul(lhs.den, rhs.den)?,
+            ),
+            Operation::Multiply => Rational::new(mul(lhs.num, rhs.num)?, mul(lhs.den, rhs.den)?),
+            Operation::Divide => {
+                if rhs.is_zero() {
+                    return Err(MathError::DivideByZero);
+                }
+                Rational::new(mul(lhs.num, rhs.den)?, mul(lhs.den, rhs.num)?)
             }
-            let results = process_monkeys(&monkeys);
-            let result = results["root"];
-            if result == 0 {
-                return test_val as isize;
-            } else if (!invert_cmp && result < 0) || (invert_cmp && result > 0) {
-                lower_bound = test_val;
-            } else {
-                upper_bound = test_val;
+        })
+    }
+}
+
+impl From<isize> for Rational {
+    fn from(val: isize) -> Self {
+        Rational::new(val as i128, 1)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "*",
+            Operation::Divide => "/",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+// The monkey tree with every branch that doesn't depend on humn folded into
+// a constant
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Expr {
+    Humn,
+    Const(Rational),
+    Operation {
+        op: Operation,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+impl Expr {
+    pub fn build(monkeys: &HashMap<String, Monkey>, name: &str) -> Result<Expr, SolveError> {
+        if name == "humn" {
+            return Ok(Expr::Humn);
+        }
+        let monkey = monkeys
+            .get(name)
+            .ok_or_else(|| SolveError::UnknownMonkey(name.to_string()))?;
+        Ok(match monkey {
+            Monkey::Number(val) => Expr::Const(Rational::from(*val)),
+            Monkey::Operation { op, lhs, rhs } => {
+                match (Expr::build(monkeys, lhs)?, Expr::build(monkeys, rhs)?) {
+                    (Expr::Const(lhs), Expr::Const(rhs)) => Expr::Const(
+                        Rational::apply(*op, lhs, rhs)
+                            .map_err(|err| SolveError::Monkey(name.to_string(), err))?,
+                    ),
+                    (lhs, rhs) => Expr::Operation {
+                        op: *op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    },
+                }
             }
-            if isize::abs(lower_bound - upper_bound) <= 1 {
-                lower_bound = -SEARCH_START;
-                upper_bound = SEARCH_START;
-                break;
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Humn => write!(f, "humn"),
+            Expr::Const(val) if val.num < 0 => write!(f, "({})", val),
+            Expr::Const(val) => write!(f, "{}", val),
+            Expr::Operation { op, lhs, rhs } => write!(f, "({} {} {})", lhs, op, rhs),
+        }
+    }
+}
+
+// root's two sides, with humn only ever on the left
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Equation {
+    pub lhs: Expr,
+    pub rhs: Rational,
+}
+
+impl Equation {
+    pub fn from_root(monkeys: &HashMap<String, Monkey>) -> Result<Equation, SolveError> {
+        let root = monkeys
+            .get("root")
+            .ok_or_else(|| SolveError::UnknownMonkey("root".to_string()))?;
+        let Monkey::Operation { lhs, rhs, .. } = root else {
+            return Err(SolveError::RootIsNumber);
+        };
+        match (Expr::build(monkeys, lhs)?, Expr::build(monkeys, rhs)?) {
+            (Expr::Const(rhs), lhs) | (lhs, Expr::Const(rhs)) => Ok(Equation { lhs, rhs }),
+            _ => Err(SolveError::HumnRepeated),
+        }
+    }
+
+    // Peels operations off the humn side one at a time, applying the inverse
+    // to the known side. Dividing by zero along the way means no value of
+    // humn satisfies the equation.
+    pub fn solve(&self) -> Result<Rational, SolveError> {
+        let mut expr = &self.lhs;
+        let mut target = self.rhs;
+        loop {
+            match expr {
+                Expr::Humn => return Ok(target),
+                // Constants are folded, so this is only reached when the
+                // whole side is one
+                Expr::Const(_) => return Err(SolveError::NoHumn),
+                Expr::Operation { op, lhs, rhs } => match (lhs.as_ref(), rhs.as_ref()) {
+                    (unknown, Expr::Const(known)) => {
+                        let inverse = match op {
+                            Operation::Add => Operation::Subtract,
+                            Operation::Subtract => Operation::Add,
+                            Operation::Multiply => Operation::Divide,
+                            Operation::Divide => Operation::Multiply,
+                        };
+                        target = Rational::apply(inverse, target, *known)?;
+                        expr = unknown;
+                    }
+                    (Expr::Const(known), unknown) => {
+                        target = match op {
+                            Operation::Add => Rational::apply(Operation::Subtract, target, *known)?,
+                            Operation::Subtract => {
+                                Rational::apply(Operation::Subtract, *known, target)?
+                            }
+                            Operation::Multiply => {
+                                Rational::apply(Operation::Divide, target, *known)?
+                            }
+                            Operation::Divide => {
+                                Rational::apply(Operation::Divide, *known, target)?
+                            }
+                        };
+                        expr = unknown;
+                    }
+                    _ => return Err(SolveError::HumnRepeated),
+                },
             }
         }
     }
-    println!(
-        "Part 2 doesn't seem to be between {} and {}",
-        -SEARCH_START, SEARCH_START
-    );
-    0
-}
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let monkeys = parse(&lines).expect("Couldn't parse file");
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
 
-    //println!("Monkeys: {:?}", monkeys);
+pub fn part2(monkeys: &HashMap<String, Monkey>) -> Result<Rational, SolveError> {
+    Equation::from_root(monkeys)?.solve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_text(text: &str) -> HashMap<String, Monkey> {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        parse(&lines).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let monkeys = parse_text(include_str!("example.txt"));
+        assert_eq!(part1(&monkeys), 152);
+        let equation = Equation::from_root(&monkeys).unwrap();
+        assert_eq!(equation.to_string(), "((4 + (2 * (humn - 3))) / 4) = 150");
+        assert_eq!(part2(&monkeys), Ok(Rational::from(301)));
+    }
 
-    println!("Part 1: {}", part1(&monkeys));
-    println!("Part 2: {}", part2(monkeys));
+    #[test]
+    fn fractions_and_negatives() {
+        let monkeys = parse_text("root: a + b\na: c - humn\nb: 7\nc: 3\nhumn: 0");
+        let equation = Equation::from_root(&monkeys).unwrap();
+        assert_eq!(equation.to_string(), "(3 - humn) = 7");
+        assert_eq!(part2(&monkeys), Ok(Rational::from(-4)));
+
+        let monkeys = parse_text("root: a + b\na: humn * c\nb: 7\nc: 2\nhumn: 0");
+        assert_eq!(part2(&monkeys), Ok(Rational::new(7, 2)));
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("root: 5\nhumn: 1", SolveError::RootIsNumber),
+            (
+                "root: a + b\na: 1\nhumn: 1",
+                SolveError::UnknownMonkey("b".to_string()),
+            ),
+            ("root: a + b\na: 1\nb: 2\nhumn: 1", SolveError::NoHumn),
+            (
+                "root: a + b\na: humn * c\nb: humn + c\nc: 2\nhumn: 1",
+                SolveError::HumnRepeated,
+            ),
+            (
+                "root: a + b\na: humn * humn\nb: 4\nhumn: 1",
+                SolveError::HumnRepeated,
+            ),
+            (
+                "root: a + humn\na: b / c\nb: 5\nc: 0\nhumn: 1",
+                SolveError::Monkey("a".to_string(), MathError::DivideByZero),
+            ),
+            (
+                "root: a + humn\na: b * b\nb: c * c\nc: 1000000000000\nhumn: 1",
+                SolveError::Monkey("a".to_string(), MathError::Overflow),
+            ),
+            (
+                "root: a + b\na: humn * c\nb: 5\nc: 0\nhumn: 1",
+                SolveError::Math(MathError::DivideByZero),
+            ),
+            (
+                "root: a + d\na: humn / d\nd: e * e\ne: 100000000000000000\nhumn: 1",
+                SolveError::Math(MathError::Overflow),
+            ),
+        ];
+        for (text, err) in cases {
+            assert_eq!(part2(&parse_text(text)), Err(err), "{}", text);
+        }
+    }
 }
@@ -0,0 +1,36 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::process;
+
+use day21::{parse, part1, part2, Equation};
+
+fn main() {
+    if env::args().count() != 2 {
+        return println!(
+            "Usage: {} [path/to/input_file]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+    let monkeys = parse(&lines).expect("Couldn't parse file");
+
+    //println!("Monkeys: {:?}", monkeys);
+
+    println!("Part 1: {}", part1(&monkeys));
+    if let Ok(equation) = Equation::from_root(&monkeys) {
+        eprintln!("{}", equation);
+    }
+    match part2(&monkeys) {
+        Ok(humn) => println!("Part 2: {}", humn),
+        Err(err) => {
+            eprintln!("Part 2 failed: {}", err);
+            process::exit(1);
+        }
+    }
+}
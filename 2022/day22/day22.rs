@@ -1,22 +1,18 @@
 use std::cmp::min;
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::ops::RangeInclusive;
 
 use colored::Colorize;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Move {
+pub enum Move {
     Left,
     Right,
     Forward(usize),
 }
 
-fn parse(lines: &Vec<String>) -> (Vec<Vec<char>>, Vec<Move>) {
+pub fn parse(lines: &[String]) -> (Vec<Vec<char>>, Vec<Move>) {
     static MOVE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+|[L,R]").unwrap());
 
     let mut it = lines.split(String::is_empty);
@@ -39,7 +35,7 @@ fn parse(lines: &Vec<String>) -> (Vec<Vec<char>>, Vec<Move>) {
             })
             .collect(),
         MOVE_RE
-            .captures_iter(&moves_str)
+            .captures_iter(moves_str)
             .map(|m| match &m[0] {
                 "L" => Move::Left,
                 "R" => Move::Right,
@@ -50,14 +46,14 @@ fn parse(lines: &Vec<String>) -> (Vec<Vec<char>>, Vec<Move>) {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     Right = 0,
     Down = 1,
     Left = 2,
     Up = 3,
 }
 impl Direction {
-    fn from_isize(v: isize) -> Direction {
+    pub fn from_isize(v: isize) -> Direction {
         match v {
             0 => Direction::Right,
             1 => Direction::Down,
@@ -66,8 +62,8 @@ impl Direction {
             _ => todo!("Can't parse {} as Direction", v),
         }
     }
-    fn to_vec2(&self) -> (isize, isize) {
-        match &self {
+    pub fn to_vec2(self) -> (isize, isize) {
+        match self {
             Direction::Left => (0, -1),
             Direction::Right => (0, 1),
             Direction::Up => (-1, 0),
@@ -76,7 +72,7 @@ impl Direction {
     }
 }
 
-fn add(
+pub fn add(
     p1: (usize, usize),
     p2: (isize, isize),
     width: usize,
@@ -95,14 +91,14 @@ fn add(
     }
 }
 
-fn print_grid(
-    grid: &Vec<Vec<char>>,
+pub fn print_grid(
+    grid: &[Vec<char>],
     pos: (usize, usize),
     history: &HashMap<(usize, usize), Direction>,
 ) {
-    println!("");
-    for y in 0..grid.len() {
-        for x in 0..grid[0].len() {
+    println!();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
             if pos == (y, x) {
                 if let Some(direction) = history.get(&(y, x)) {
                     let c = match direction {
@@ -113,7 +109,7 @@ fn print_grid(
                     };
 
                     //if pos == (y, x) {
-                    print!("{}", format!("{}", c).bold().red());
+                    print!("{}", c.bold().red());
                 }
                 /*
                 } else {
@@ -121,27 +117,24 @@ fn print_grid(
                 }
                 */
             } else {
-                print!("{}", grid[y][x]);
+                print!("{}", tile);
             }
         }
-        println!("");
+        println!();
     }
-    println!("");
+    println!();
 }
 
-fn wrap_pos_simple_2d(
-    grid: &Vec<Vec<char>>,
+pub fn wrap_pos_simple_2d(
+    grid: &[Vec<char>],
     pos: (usize, usize),
     direction: Direction,
 ) -> (usize, usize) {
-    let (width, height) = grid_dims(&grid);
+    let (width, height) = grid_dims(grid);
     match direction {
         Direction::Left => (
             pos.0,
-            if let Some(right_x) = (pos.1..width)
-                .into_iter()
-                .position(|x| grid[pos.0][x] == ' ')
-            {
+            if let Some(right_x) = (pos.1..width).position(|x| grid[pos.0][x] == ' ') {
                 right_x - 1
             } else {
                 width - 1
@@ -149,22 +142,14 @@ fn wrap_pos_simple_2d(
         ),
         Direction::Right => (
             pos.0,
-            if let Some(left_x) = (0..pos.1)
-                .into_iter()
-                .rev()
-                .position(|x| grid[pos.0][x] == ' ')
-            {
+            if let Some(left_x) = (0..pos.1).rev().position(|x| grid[pos.0][x] == ' ') {
                 left_x + 1
             } else {
                 0
             },
         ),
         Direction::Up => (
-            if let Some(down_y) = (pos.0..height)
-                .rev()
-                .into_iter()
-                .position(|y| grid[y][pos.1] == ' ')
-            {
+            if let Some(down_y) = (pos.0..height).rev().position(|y| grid[y][pos.1] == ' ') {
                 down_y + 1
             } else {
                 height - 1
@@ -172,11 +157,7 @@ fn wrap_pos_simple_2d(
             pos.1,
         ),
         Direction::Down => (
-            if let Some(up_y) = (0..pos.0)
-                .rev()
-                .into_iter()
-                .position(|y| grid[y][pos.1] == ' ')
-            {
+            if let Some(up_y) = (0..pos.0).rev().position(|y| grid[y][pos.1] == ' ') {
                 up_y + 1
             } else {
                 0
@@ -186,410 +167,217 @@ fn wrap_pos_simple_2d(
     }
 }
 
-fn segment_bounds_example(
-    dims: (usize, usize),
-) -> (Vec<RangeInclusive<usize>>, Vec<RangeInclusive<usize>>) {
-    let (width, height) = dims;
-    let seg_width = width / 4;
-    let seg_height = height / 3;
-    (
-        (0..4)
-            .map(|x| (seg_width * x)..=(seg_width * (x + 1) - 1))
-            .collect(),
-        (0..3)
-            .map(|y| (seg_height * y)..=(seg_height * (y + 1) - 1))
-            .collect(),
-    )
+pub fn grid_dims(grid: &[Vec<char>]) -> (usize, usize) {
+    (grid[0].len(), grid.len())
 }
 
-fn get_section_example(pos: (usize, usize), dims: (usize, usize)) -> usize {
-    let (x_segs, y_segs) = segment_bounds_example(dims);
-    let y_seg = y_segs
-        .into_iter()
-        .position(|seg| seg.contains(&pos.0))
-        .expect("Couldn't get seg from y");
-    let x_seg = x_segs
-        .into_iter()
-        .position(|seg| seg.contains(&pos.1))
-        .expect("Couldn't get seg from x");
-    match y_seg {
-        0 => 1,
-        1 => match x_seg {
-            0 => 2,
-            1 => 3,
-            2 => 4,
-            _ => todo!("Couldn't get section from pos {:?}", pos),
-        },
-        2 => match x_seg {
-            2 => 5,
-            3 => 6,
-            _ => todo!("Couldn't get section from pos {:?}", pos),
-        },
-        _ => todo!("Couldn't get section from pos {:?}", pos),
-    }
-}
+pub type Vec3 = (isize, isize, isize);
 
-fn grid_dims(grid: &Vec<Vec<char>>) -> (usize, usize) {
-    (grid[0].len(), grid.len())
+fn neg(v: Vec3) -> Vec3 {
+    (-v.0, -v.1, -v.2)
 }
 
-fn wrap_pos_cube_example(
-    grid: &Vec<Vec<char>>,
-    pos: (usize, usize),
-    direction: Direction,
-) -> ((usize, usize), Direction) {
-    let dims = grid_dims(&grid);
-    let (width, height) = dims;
-    let (x_segs, y_segs) = segment_bounds_example(dims);
-    let section = get_section_example(pos, grid_dims(grid));
-    match section {
-        1 => match direction {
-            Direction::Right => ((height - 1 - pos.0, width - 1), Direction::Left),
-            Direction::Up => ((height - 1, pos.1), Direction::Up),
-            Direction::Left => (
-                (*y_segs[1].start(), x_segs[1].start() + pos.0),
-                Direction::Down,
-            ),
-            Direction::Down => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        2 => match direction {
-            Direction::Left => (
-                (height - 1, pos.0 - y_segs[1].start() + x_segs[3].start()),
-                Direction::Up,
-            ),
-            Direction::Up => (
-                (0, x_segs[0].end() - pos.1 + x_segs[2].start()),
-                Direction::Down,
-            ),
-            Direction::Down => (
-                (height - 1, x_segs[0].end() - pos.1 + x_segs[2].start()),
-                Direction::Up,
-            ),
-            Direction::Right => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        3 => match direction {
-            Direction::Up => (
-                (pos.1 - x_segs[1].start(), *x_segs[2].start()),
-                Direction::Right,
-            ),
-            Direction::Down => (
-                (
-                    x_segs[1].end() - pos.1 + y_segs[2].start(),
-                    *x_segs[2].start(),
-                ),
-                Direction::Right,
-            ),
-            Direction::Left => todo!(
-                "Shouldn't have wrapped going {:?} from {} ({:?})",
-                direction,
-                section,
-                pos
-            ),
-            Direction::Right => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        4 => match direction {
-            Direction::Right => (
-                (
-                    *y_segs[2].start(),
-                    y_segs[1].end() - pos.0 + x_segs[3].start(),
-                ),
-                Direction::Down,
-            ),
-            Direction::Down => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Left => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Up => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        5 => match direction {
-            Direction::Left => (
-                (
-                    *y_segs[1].end(),
-                    y_segs[2].end() - pos.0 + x_segs[1].start(),
-                ),
-                Direction::Up,
-            ),
-            Direction::Down => ((*y_segs[1].end(), x_segs[2].end() - pos.1), Direction::Up),
-            Direction::Up => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Right => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        6 => match direction {
-            Direction::Right => ((y_segs[2].end() - pos.1, *x_segs[2].end()), Direction::Left),
-            Direction::Up => (
-                (
-                    x_segs[3].end() - pos.1 + y_segs[1].start(),
-                    *x_segs[2].end(),
-                ),
-                Direction::Left,
-            ),
-            Direction::Down => (
-                (x_segs[3].end() - pos.1 + y_segs[1].start(), 0),
-                Direction::Right,
-            ),
-            Direction::Left => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        _ => todo!("Couldn't wrap from section {}", section),
-    }
+// One face of the folded cube, with the 3D directions its grid axes point in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Face {
+    // Top left grid position of the face
+    pub origin: (usize, usize),
+    // Outward normal of the face
+    pub normal: Vec3,
+    // 3D direction of increasing x (column) on the face
+    pub right: Vec3,
+    // 3D direction of increasing y (row) on the face
+    pub down: Vec3,
 }
 
-fn segment_bounds_input(
-    dims: (usize, usize),
-) -> (Vec<RangeInclusive<usize>>, Vec<RangeInclusive<usize>>) {
-    let (width, height) = dims;
-    let seg_width = width / 3;
-    let seg_height = height / 4;
-    (
-        (0..3)
-            .map(|x| (seg_width * x)..=(seg_width * (x + 1) - 1))
-            .collect(),
-        (0..4)
-            .map(|y| (seg_height * y)..=(seg_height * (y + 1) - 1))
-            .collect(),
-    )
-}
+impl Face {
+    pub fn to_vec3(&self, direction: Direction) -> Vec3 {
+        match direction {
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => neg(self.right),
+            Direction::Up => neg(self.down),
+        }
+    }
 
-fn get_section_input(pos: (usize, usize), dims: (usize, usize)) -> usize {
-    let (x_segs, y_segs) = segment_bounds_input(dims);
-    let y_seg = y_segs
-        .into_iter()
-        .position(|seg| seg.contains(&pos.0))
-        .expect("Couldn't get seg from y");
-    let x_seg = x_segs
+    pub fn direction_of(&self, v: Vec3) -> Direction {
+        [
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+            Direction::Up,
+        ]
         .into_iter()
-        .position(|seg| seg.contains(&pos.1))
-        .expect("Couldn't get seg from x");
-    match x_seg {
-        0 => match y_seg {
-            2 => 1,
-            3 => 2,
-            _ => todo!("Couldn't get section from pos {:?}", pos),
-        },
-        1 => match y_seg {
-            0 => 5,
-            1 => 4,
-            2 => 3,
-            _ => todo!("Couldn't get section from pos {:?}", pos),
-        },
-        2 => 6,
-        _ => todo!("Couldn't get section from pos {:?}", pos),
+        .find(|direction| self.to_vec3(*direction) == v)
+        .unwrap_or_else(|| panic!("{:?} isn't in the plane of face {:?}", v, self))
     }
 }
 
-fn wrap_pos_cube_input(
-    grid: &Vec<Vec<char>>,
-    pos: (usize, usize),
-    direction: Direction,
-) -> ((usize, usize), Direction) {
-    let dims = grid_dims(&grid);
-    let (x_segs, y_segs) = segment_bounds_input(dims);
-    let section = get_section_input(pos, grid_dims(grid));
-    match section {
-        1 => match direction {
-            Direction::Up => (
-                (
-                    pos.1 - x_segs[0].start() + y_segs[1].start(),
-                    *x_segs[1].start(),
-                ),
-                Direction::Right,
-            ),
-            Direction::Left => (
-                (
-                    y_segs[2].end() - pos.0 + y_segs[0].start(),
-                    *x_segs[1].start(),
+// The grid folded up into a cube, works for any of the 11 cube nets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cube {
+    pub face_size: usize,
+    pub faces: Vec<Face>,
+    // Index into faces by (row, column) of the face in the net
+    pub face_at: HashMap<(usize, usize), usize>,
+}
+
+impl Cube {
+    pub fn fold(grid: &[Vec<char>]) -> Cube {
+        let area = grid.iter().flatten().filter(|c| **c != ' ').count();
+        let face_size = (1..=area)
+            .find(|size| size * size * 6 >= area)
+            .expect("Couldn't get face size");
+        assert_eq!(
+            face_size * face_size * 6,
+            area,
+            "Grid area isn't that of 6 square faces"
+        );
+
+        let (width, height) = grid_dims(grid);
+        let net_positions = (0..height / face_size)
+            .flat_map(|y| (0..width / face_size).map(move |x| (y, x)))
+            .filter(|(y, x)| grid[y * face_size][x * face_size] != ' ')
+            .collect::<Vec<_>>();
+        assert_eq!(net_positions.len(), 6, "Grid doesn't have 6 faces");
+
+        // Walk the net from the first face, rolling the cube over the edge
+        // shared with each newly reached face
+        let mut faces = HashMap::<(usize, usize), Face>::new();
+        let mut to_visit = vec![(
+            net_positions[0],
+            Face {
+                origin: (
+                    net_positions[0].0 * face_size,
+                    net_positions[0].1 * face_size,
                 ),
+                normal: (0, 0, -1),
+                right: (1, 0, 0),
+                down: (0, 1, 0),
+            },
+        )];
+        while let Some((net_pos, face)) = to_visit.pop() {
+            if faces.contains_key(&net_pos) {
+                continue;
+            }
+            faces.insert(net_pos, face);
+            for direction in [
                 Direction::Right,
-            ),
-            Direction::Right => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Down => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        2 => match direction {
-            Direction::Left => (
-                (
-                    *y_segs[0].start(),
-                    pos.0 - y_segs[3].start() + x_segs[1].start(),
-                ),
-                Direction::Down,
-            ),
-            Direction::Right => (
-                (
-                    *y_segs[2].end(),
-                    pos.0 - y_segs[3].start() + x_segs[1].start(),
-                ),
-                Direction::Up,
-            ),
-            Direction::Down => (
-                (
-                    *y_segs[0].start(),
-                    pos.1 - x_segs[0].start() + x_segs[2].start(),
-                ),
                 Direction::Down,
-            ),
-            Direction::Up => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        3 => match direction {
-            Direction::Right => (
-                (
-                    y_segs[2].end() - pos.0 + y_segs[0].start(),
-                    *x_segs[2].end(),
-                ),
-                Direction::Left,
-            ),
-            Direction::Down => (
-                (
-                    pos.1 - x_segs[1].start() + y_segs[3].start(),
-                    *x_segs[0].end(),
-                ),
-                Direction::Left,
-            ),
-            Direction::Left => todo!(
-                "Shouldn't have wrapped going {:?} from {} ({:?})",
-                direction,
-                section,
-                pos
-            ),
-            Direction::Up => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        4 => match direction {
-            Direction::Right => (
-                (
-                    *y_segs[0].end(),
-                    pos.0 - y_segs[1].start() + x_segs[2].start(),
-                ),
-                Direction::Up,
-            ),
-            Direction::Left => (
-                (*y_segs[2].start(), pos.0 - y_segs[1].start()),
-                Direction::Down,
-            ),
-            Direction::Down => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Up => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        5 => match direction {
-            Direction::Left => (
-                (
-                    y_segs[0].end() - pos.0 + y_segs[2].start(),
-                    *x_segs[0].start(),
-                ),
-                Direction::Right,
-            ),
-            Direction::Up => (
-                (
-                    pos.1 - x_segs[1].start() + y_segs[3].start(),
-                    *x_segs[0].start(),
-                ),
-                Direction::Right,
-            ),
-            Direction::Down => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-            Direction::Right => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        6 => match direction {
-            Direction::Right => (
-                (
-                    y_segs[0].end() - pos.0 + y_segs[2].start(),
-                    *x_segs[1].end(),
-                ),
                 Direction::Left,
-            ),
-            Direction::Down => (
-                (
-                    pos.1 - x_segs[2].start() + y_segs[1].start(),
-                    *x_segs[1].end(),
-                ),
-                Direction::Left,
-            ),
-            Direction::Up => (
-                (
-                    *y_segs[3].end(),
-                    pos.1 - x_segs[2].start() + x_segs[0].start(),
-                ),
                 Direction::Up,
+            ] {
+                let (dy, dx) = direction.to_vec2();
+                let next_pos = (net_pos.0 as isize + dy, net_pos.1 as isize + dx);
+                if next_pos.0 < 0 || next_pos.1 < 0 {
+                    continue;
+                }
+                let next_pos = (next_pos.0 as usize, next_pos.1 as usize);
+                if !net_positions.contains(&next_pos) || faces.contains_key(&next_pos) {
+                    continue;
+                }
+                let across = face.to_vec3(direction);
+                let rolled = |v: Vec3| {
+                    if v == across {
+                        neg(face.normal)
+                    } else if v == neg(across) {
+                        face.normal
+                    } else {
+                        v
+                    }
+                };
+                to_visit.push((
+                    next_pos,
+                    Face {
+                        origin: (next_pos.0 * face_size, next_pos.1 * face_size),
+                        normal: across,
+                        right: rolled(face.right),
+                        down: rolled(face.down),
+                    },
+                ));
+            }
+        }
+        assert_eq!(faces.len(), 6, "Grid faces aren't connected");
+
+        let face_at = net_positions
+            .iter()
+            .enumerate()
+            .map(|(i, net_pos)| (*net_pos, i))
+            .collect();
+        let faces = net_positions
+            .iter()
+            .map(|net_pos| faces[net_pos])
+            .collect::<Vec<_>>();
+        for (i, face) in faces.iter().enumerate() {
+            assert!(
+                faces[..i].iter().all(|other| other.normal != face.normal),
+                "Grid folds two faces onto the same side of the cube"
+            );
+        }
+
+        Cube {
+            face_size,
+            faces,
+            face_at,
+        }
+    }
+
+    pub fn face_of(&self, pos: (usize, usize)) -> &Face {
+        &self.faces[self.face_at[&(pos.0 / self.face_size, pos.1 / self.face_size)]]
+    }
+
+    // Moves off the edge of the face pos is on, onto the face glued to that edge
+    pub fn wrap(&self, pos: (usize, usize), direction: Direction) -> ((usize, usize), Direction) {
+        let face = self.face_of(pos);
+        let last = self.face_size - 1;
+        let local = (pos.0 - face.origin.0, pos.1 - face.origin.1);
+
+        let travel = face.to_vec3(direction);
+        let next_face = self
+            .faces
+            .iter()
+            .find(|other| other.normal == travel)
+            .expect("Couldn't find face across edge");
+
+        // Distance along the shared edge, measured in the 3D direction edge_axis
+        let (edge_axis, along) = match direction {
+            Direction::Left | Direction::Right => (face.down, local.0),
+            Direction::Up | Direction::Down => (face.right, local.1),
+        };
+        let mut next_local = match next_face.direction_of(edge_axis) {
+            Direction::Right => (0, along),
+            Direction::Left => (0, last - along),
+            Direction::Down => (along, 0),
+            Direction::Up => (last - along, 0),
+        };
+        // Enter from the side of the next face that touches the current face
+        match next_face.direction_of(face.normal) {
+            Direction::Right => next_local.1 = last,
+            Direction::Left => next_local.1 = 0,
+            Direction::Down => next_local.0 = last,
+            Direction::Up => next_local.0 = 0,
+        }
+
+        (
+            (
+                next_face.origin.0 + next_local.0,
+                next_face.origin.1 + next_local.1,
             ),
-            Direction::Left => todo!(
-                "Shouldn't have wrapped going {:?} from {}",
-                direction,
-                section
-            ),
-        },
-        _ => todo!("Couldn't wrap from section {}", section),
+            next_face.direction_of(neg(face.normal)),
+        )
     }
 }
 
 static DEBUG: bool = false;
 static DEBUG_WRAPPING: bool = false;
-static IS_INPUT: bool = true;
-
-fn walk_grid(
-    grid: &Vec<Vec<char>>,
-    moves: &Vec<Move>,
-    is_cube: bool,
-) -> ((usize, usize), Direction) {
-    let (width, height) = grid_dims(&grid);
+
+pub fn walk_grid(grid: &[Vec<char>], moves: &[Move], is_cube: bool) -> ((usize, usize), Direction) {
+    let (width, height) = grid_dims(grid);
+    let cube = if is_cube {
+        Some(Cube::fold(grid))
+    } else {
+        None
+    };
     let mut pos = (
         0usize,
         min(
@@ -603,7 +391,7 @@ fn walk_grid(
         .into_iter()
         .collect::<HashMap<(usize, usize), Direction>>();
     if DEBUG {
-        print_grid(&grid, pos, &history);
+        print_grid(grid, pos, &history);
     }
     for m in moves {
         //println!("Move: {:?}", m);
@@ -629,14 +417,10 @@ fn walk_grid(
                         }
                         '#' => break,
                         ' ' => {
-                            let (new_pos, new_dir) = if is_cube {
-                                if IS_INPUT {
-                                    wrap_pos_cube_input(&grid, pos, direction)
-                                } else {
-                                    wrap_pos_cube_example(&grid, pos, direction)
-                                }
+                            let (new_pos, new_dir) = if let Some(cube) = &cube {
+                                cube.wrap(pos, direction)
                             } else {
-                                (wrap_pos_simple_2d(&grid, pos, direction), direction)
+                                (wrap_pos_simple_2d(grid, pos, direction), direction)
                             };
                             if grid[new_pos.0][new_pos.1] == '.' {
                                 if DEBUG_WRAPPING {
@@ -645,7 +429,7 @@ fn walk_grid(
                                         pos, direction, new_pos, new_dir
                                     );
 
-                                    print_grid(&grid, pos, &history);
+                                    print_grid(grid, pos, &history);
                                 }
 
                                 pos = new_pos;
@@ -653,7 +437,7 @@ fn walk_grid(
                                 history.insert(pos, direction);
 
                                 if DEBUG_WRAPPING {
-                                    print_grid(&grid, pos, &history);
+                                    print_grid(grid, pos, &history);
                                 }
                             }
                         }
@@ -669,40 +453,122 @@ fn walk_grid(
         }
         history.insert(pos, direction);
         if DEBUG {
-            print_grid(&grid, pos, &history);
+            print_grid(grid, pos, &history);
         }
     }
     (pos, direction)
 }
 
-fn part1(grid: &Vec<Vec<char>>, moves: &Vec<Move>) -> usize {
-    let (pos, direction) = walk_grid(&grid, moves, false);
-    1000 * dbg!(pos.0 + 1) + 4 * dbg!(pos.1 + 1) + dbg!(direction) as usize
+pub fn part1(grid: &[Vec<char>], moves: &[Move]) -> usize {
+    let (pos, direction) = walk_grid(grid, moves, false);
+    1000 * (pos.0 + 1) + 4 * (pos.1 + 1) + direction as usize
 }
 
-fn part2(grid: &Vec<Vec<char>>, moves: &Vec<Move>) -> usize {
-    let (pos, direction) = walk_grid(&grid, moves, true);
-    1000 * dbg!(pos.0 + 1) + 4 * dbg!(pos.1 + 1) + dbg!(direction) as usize
+pub fn part2(grid: &[Vec<char>], moves: &[Move]) -> usize {
+    let (pos, direction) = walk_grid(grid, moves, true);
+    1000 * (pos.0 + 1) + 4 * (pos.1 + 1) + direction as usize
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The 11 cube nets, one character per face
+    static NETS: [&str; 11] = [
+        "#...\n####\n#...",
+        "#...\n####\n.#..",
+        "#...\n####\n..#.",
+        "#...\n####\n...#",
+        ".#..\n####\n.#..",
+        ".#..\n####\n..#.",
+        "##..\n.###\n..#.",
+        "##..\n.###\n...#",
+        "##..\n.##.\n..##",
+        "###..\n..###",
+        "#..\n###\n.#.\n.#.",
+    ];
+
+    fn net_grid(net: &str, face_size: usize) -> Vec<Vec<char>> {
+        let rows = net.lines().collect::<Vec<_>>();
+        let width = rows.iter().map(|row| row.len()).max().unwrap();
+        rows.iter()
+            .flat_map(|row| {
+                let row = (0..width)
+                    .flat_map(|x| {
+                        let c = if row.chars().nth(x) == Some('#') {
+                            '.'
+                        } else {
+                            ' '
+                        };
+                        vec![c; face_size]
+                    })
+                    .collect::<Vec<_>>();
+                vec![row; face_size]
+            })
+            .collect()
+    }
+
+    fn scale(v: Vec3, s: isize) -> Vec3 {
+        (v.0 * s, v.1 * s, v.2 * s)
     }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
 
-    let (grid, moves) = parse(&lines);
+    fn sum(vs: &[Vec3]) -> Vec3 {
+        vs.iter()
+            .fold((0, 0, 0), |a, v| (a.0 + v.0, a.1 + v.1, a.2 + v.2))
+    }
 
-    //println!("Parsed: {:?},{:?}", grid, moves);
+    // Center of a grid cell on the folded cube, in units of half a cell
+    fn center(cube: &Cube, pos: (usize, usize)) -> Vec3 {
+        let face = cube.face_of(pos);
+        let size = cube.face_size as isize;
+        let y = (pos.0 - face.origin.0) as isize;
+        let x = (pos.1 - face.origin.1) as isize;
+        sum(&[
+            scale(face.normal, size),
+            scale(face.right, 2 * x + 1 - size),
+            scale(face.down, 2 * y + 1 - size),
+        ])
+    }
 
-    println!("Part 1: {}", part1(&grid, &moves));
-    println!("Part 2: {}", part2(&grid, &moves));
+    #[test]
+    fn test_wrap_glues_adjacent_cells() {
+        for net in NETS {
+            for face_size in [1, 3] {
+                let grid = net_grid(net, face_size);
+                let cube = Cube::fold(&grid);
+                let (width, height) = grid_dims(&grid);
+                for (y, x) in (0..height).flat_map(|y| (0..width).map(move |x| (y, x))) {
+                    if grid[y][x] == ' ' {
+                        continue;
+                    }
+                    for direction in [
+                        Direction::Right,
+                        Direction::Down,
+                        Direction::Left,
+                        Direction::Up,
+                    ] {
+                        let next = add((y, x), direction.to_vec2(), width, height);
+                        if next.is_some_and(|(ny, nx)| grid[ny][nx] != ' ') {
+                            continue;
+                        }
+                        let face = cube.face_of((y, x));
+                        let (new_pos, new_dir) = cube.wrap((y, x), direction);
+                        assert_eq!(
+                            center(&cube, new_pos),
+                            sum(&[
+                                center(&cube, (y, x)),
+                                face.to_vec3(direction),
+                                neg(face.normal)
+                            ]),
+                            "{} wrapping {:?} from {:?}",
+                            net,
+                            direction,
+                            (y, x)
+                        );
+                        assert_eq!(cube.face_of(new_pos).to_vec3(new_dir), neg(face.normal));
+                    }
+                }
+            }
+        }
+    }
 }
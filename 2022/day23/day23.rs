@@ -1,31 +1,27 @@
 use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Point = (isize, isize);
+pub type Point = (isize, isize);
 
-fn parse(lines: &Vec<String>) -> HashSet<Point> {
+pub fn parse(lines: &[String]) -> HashSet<Point> {
     lines
         .iter()
         .enumerate()
-        .map(|(y, row)| {
+        .flat_map(|(y, row)| {
             row.chars()
                 .positions(|c| c == '#')
                 .map(move |x| (x as isize, y as isize))
         })
-        .flatten()
         .collect()
 }
 
-fn add(p1: Point, p2: Point) -> Point {
+pub fn add(p1: Point, p2: Point) -> Point {
     (p1.0 + p2.0, p1.1 + p2.1)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     North = 0,
     South = 1,
     West = 2,
@@ -33,7 +29,7 @@ enum Direction {
     COUNT = 4,
 }
 impl Direction {
-    fn from_isize(v: isize) -> Direction {
+    pub fn from_isize(v: isize) -> Direction {
         match v {
             0 => Direction::North,
             1 => Direction::South,
@@ -43,7 +39,7 @@ impl Direction {
             _ => todo!("Can't parse {} as Direction", v),
         }
     }
-    fn to_vec2(&self) -> Point {
+    pub fn to_vec2(&self) -> Point {
         match &self {
             Direction::West => (-1, 0),
             Direction::East => (1, 0),
@@ -52,7 +48,7 @@ impl Direction {
             Direction::COUNT => todo!("Can't get vec2 from COUNT"),
         }
     }
-    fn fields_to_check(&self) -> [Point; 3] {
+    pub fn fields_to_check(&self) -> [Point; 3] {
         match self {
             Direction::West => [(-1, 0), (-1, -1), (-1, 1)],
             Direction::East => [(1, 0), (1, -1), (1, 1)],
@@ -63,7 +59,7 @@ impl Direction {
     }
 }
 
-fn elf_bounds(elves: &HashSet<Point>) -> (isize, isize, isize, isize) {
+pub fn elf_bounds(elves: &HashSet<Point>) -> (isize, isize, isize, isize) {
     (
         elves
             .iter()
@@ -88,8 +84,8 @@ fn elf_bounds(elves: &HashSet<Point>) -> (isize, isize, isize, isize) {
     )
 }
 
-fn print_grid(elves: &HashSet<Point>) {
-    let (min_x, min_y, max_x, max_y) = elf_bounds(&elves);
+pub fn print_grid(elves: &HashSet<Point>) {
+    let (min_x, min_y, max_x, max_y) = elf_bounds(elves);
     for y in min_y..=max_y {
         for x in min_x..=max_x {
             if elves.contains(&(x, y)) {
@@ -98,16 +94,16 @@ fn print_grid(elves: &HashSet<Point>) {
                 print!(".");
             }
         }
-        println!("");
+        println!();
     }
-    println!("");
+    println!();
 }
 
 static DEBUG: bool = false;
 
 // Returns the number of empty spaces in the elf bounds, unless rounds is None or high enough to
 // reach the equilibrium state, in which case returns the round number where it's reached
-fn simulate_rounds(mut elves: HashSet<Point>, rounds: Option<usize>) -> usize {
+pub fn simulate_rounds(mut elves: HashSet<Point>, rounds: Option<usize>) -> usize {
     let mut first_direction = Direction::North;
     if DEBUG {
         println!("== Initial State ==");
@@ -119,7 +115,6 @@ fn simulate_rounds(mut elves: HashSet<Point>, rounds: Option<usize>) -> usize {
         }
 
         let direction_check_order = (0..Direction::COUNT as isize)
-            .into_iter()
             .map(|d_i| {
                 Direction::from_isize((first_direction as isize + d_i) % Direction::COUNT as isize)
             })
@@ -140,7 +135,7 @@ fn simulate_rounds(mut elves: HashSet<Point>, rounds: Option<usize>) -> usize {
 
                 if let (true, Some(possible_direction)) = (
                     possible_directions.len() != direction_check_order.len(),
-                    possible_directions.iter().next(),
+                    possible_directions.first(),
                 ) {
                     Some((add(*elf, possible_direction.to_vec2()), *elf))
                 } else {
@@ -190,31 +185,293 @@ fn simulate_rounds(mut elves: HashSet<Point>, rounds: Option<usize>) -> usize {
         .sum()
 }
 
-fn part1(elves: &HashSet<Point>) -> usize {
-    simulate_rounds(elves.clone(), Some(10))
+// Elves as one bitset per row, with bit i of a row's words standing for
+// x = origin.0 + i. There are always at least two empty rows and columns
+// around the elves, so nobody can step off the grid in a round.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ElfGrid {
+    pub origin: Point,
+    pub words: usize,
+    pub rows: Vec<Vec<u64>>,
 }
 
-fn part2(elves: &HashSet<Point>) -> usize {
-    simulate_rounds(elves.clone(), None)
+// Row shifted so that bit i holds what was at bit i - 1, so each cell sees
+// its western neighbour
+fn from_west(row: &[u64]) -> Vec<u64> {
+    let mut carry = 0;
+    row.iter()
+        .map(|word| {
+            let shifted = word << 1 | carry;
+            carry = word >> 63;
+            shifted
+        })
+        .collect()
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
+// Row shifted so that bit i holds what was at bit i + 1
+fn from_east(row: &[u64]) -> Vec<u64> {
+    let mut carry = 0;
+    let mut shifted: Vec<u64> = row
+        .iter()
+        .rev()
+        .map(|word| {
+            let shifted = word >> 1 | carry << 63;
+            carry = word & 1;
+            shifted
+        })
         .collect();
+    shifted.reverse();
+    shifted
+}
+
+fn or(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(a, b)| a | b).collect()
+}
+
+fn and(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(a, b)| a & b).collect()
+}
+
+fn and_not(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(a, b)| a & !b).collect()
+}
 
-    let elves = parse(&lines);
-    //println!("Elves: {:?}", elves);
+impl ElfGrid {
+    const PADDING: isize = 16;
 
-    println!("Part 1: {}", part1(&elves));
-    println!("Part 2: {}", part2(&elves));
+    pub fn new(elves: &HashSet<Point>) -> ElfGrid {
+        let (min_x, min_y, max_x, max_y) = elf_bounds(elves);
+        let origin = (min_x - ElfGrid::PADDING, min_y - ElfGrid::PADDING);
+        let width = (max_x - origin.0 + 1 + ElfGrid::PADDING) as usize;
+        let height = (max_y - origin.1 + 1 + ElfGrid::PADDING) as usize;
+        let words = width.div_ceil(64);
+        let mut rows = vec![vec![0u64; words]; height];
+        for elf in elves {
+            let (x, y) = ((elf.0 - origin.0) as usize, (elf.1 - origin.1) as usize);
+            rows[y][x / 64] |= 1 << (x % 64);
+        }
+        ElfGrid {
+            origin,
+            words,
+            rows,
+        }
+    }
+
+    pub fn elves(&self) -> HashSet<Point> {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                (0..self.words * 64)
+                    .filter(move |x| row[x / 64] & 1 << (x % 64) != 0)
+                    .map(move |x| (self.origin.0 + x as isize, self.origin.1 + y as isize))
+            })
+            .collect()
+    }
+
+    // Rebuilds the grid with fresh padding if any elf is next to its edge
+    fn ensure_padding(&mut self) {
+        let width = self.words * 64;
+        let near_edge =
+            |row: &Vec<u64>| row[0] & 0b11 != 0 || row[self.words - 1] & (0b11 << 62) != 0;
+        let height = self.rows.len();
+        if self.rows[..2]
+            .iter()
+            .chain(&self.rows[height - 2..])
+            .any(|row| row.iter().any(|word| *word != 0))
+            || self.rows.iter().any(near_edge)
+            || width < 4
+        {
+            *self = ElfGrid::new(&self.elves());
+        }
+    }
+
+    // One round of proposals and moves, checking the directions in the
+    // given order. Returns whether any elf moved.
+    pub fn round(&mut self, direction_check_order: &[Direction]) -> bool {
+        self.ensure_padding();
+        let height = self.rows.len();
+        let empty = vec![0u64; self.words];
+        let row = |y: isize| -> &Vec<u64> {
+            if (0..height as isize).contains(&y) {
+                &self.rows[y as usize]
+            } else {
+                &empty
+            }
+        };
+
+        // Proposals by direction, as [north, south, west, east] per row
+        let mut proposals =
+            vec![[empty.clone(), empty.clone(), empty.clone(), empty.clone()]; height];
+        let mut anyone_proposed = false;
+        for (y, row_proposals) in proposals.iter_mut().enumerate() {
+            let (north, here, south) = (row(y as isize - 1), row(y as isize), row(y as isize + 1));
+            let west = or(&or(&from_west(north), &from_west(here)), &from_west(south));
+            let east = or(&or(&from_east(north), &from_east(here)), &from_east(south));
+            let north_row = or(&or(north, &from_west(north)), &from_east(north));
+            let south_row = or(&or(south, &from_west(south)), &from_east(south));
+            // What blocks each direction, in the same order as the proposals
+            let blocked = [&north_row, &south_row, &west, &east];
+
+            // Elves with nobody around them stay put
+            let crowded = or(&or(&north_row, &south_row), &or(&west, &east));
+            let mut undecided = and(here, &crowded);
+            for direction in direction_check_order {
+                let proposing = and_not(&undecided, blocked[*direction as usize]);
+                undecided = and_not(&undecided, &proposing);
+                anyone_proposed |= proposing.iter().any(|word| *word != 0);
+                row_proposals[*direction as usize] = proposing;
+            }
+        }
+        if !anyone_proposed {
+            return false;
+        }
+
+        // Only elves coming from opposite sides can propose the same cell
+        let proposal = |y: isize, direction: Direction| -> &Vec<u64> {
+            if (0..height as isize).contains(&y) {
+                &proposals[y as usize][direction as usize]
+            } else {
+                &empty
+            }
+        };
+        let moves: Vec<[Vec<u64>; 4]> = (0..height as isize)
+            .map(|y| {
+                let north = proposal(y, Direction::North);
+                let south = proposal(y, Direction::South);
+                let west = proposal(y, Direction::West);
+                let east = proposal(y, Direction::East);
+                [
+                    and_not(north, proposal(y - 2, Direction::South)),
+                    and_not(south, proposal(y + 2, Direction::North)),
+                    and_not(west, &from_west(&from_west(east))),
+                    and_not(east, &from_east(&from_east(west))),
+                ]
+            })
+            .collect();
+        let moved = |y: isize, direction: Direction| -> &Vec<u64> {
+            if (0..height as isize).contains(&y) {
+                &moves[y as usize][direction as usize]
+            } else {
+                &empty
+            }
+        };
+        self.rows = (0..height as isize)
+            .map(|y| {
+                let leaving = moves[y as usize]
+                    .iter()
+                    .fold(empty.clone(), |leaving, bits| or(&leaving, bits));
+                let arriving = or(
+                    &or(
+                        moved(y + 1, Direction::North),
+                        moved(y - 1, Direction::South),
+                    ),
+                    &or(
+                        &from_east(moved(y, Direction::West)),
+                        &from_west(moved(y, Direction::East)),
+                    ),
+                );
+                or(&and_not(row(y), &leaving), &arriving)
+            })
+            .collect();
+        true
+    }
+}
+
+// Same as simulate_rounds, on an ElfGrid
+pub fn simulate_rounds_bitset(elves: HashSet<Point>, rounds: Option<usize>) -> usize {
+    let mut grid = ElfGrid::new(&elves);
+    let mut first_direction = Direction::North;
+    for round in 1..=rounds.unwrap_or(usize::MAX) {
+        let direction_check_order = (0..Direction::COUNT as isize)
+            .map(|d_i| {
+                Direction::from_isize((first_direction as isize + d_i) % Direction::COUNT as isize)
+            })
+            .collect::<Vec<_>>();
+        if !grid.round(&direction_check_order) {
+            return round;
+        }
+        first_direction =
+            Direction::from_isize((first_direction as isize + 1) % Direction::COUNT as isize);
+        if DEBUG {
+            println!("== end of Round {} ==", round);
+            print_grid(&grid.elves());
+        }
+    }
+    let elves = grid.elves();
+    let (min_x, min_y, max_x, max_y) = elf_bounds(&elves);
+    ((max_x - min_x + 1) * (max_y - min_y + 1)) as usize - elves.len()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Engine {
+    HashSet,
+    Bitset,
+}
+
+pub fn simulate(engine: Engine, elves: &HashSet<Point>, rounds: Option<usize>) -> usize {
+    match engine {
+        Engine::HashSet => simulate_rounds(elves.clone(), rounds),
+        Engine::Bitset => simulate_rounds_bitset(elves.clone(), rounds),
+    }
+}
+
+pub fn part1(elves: &HashSet<Point>, engine: Engine) -> usize {
+    simulate(engine, elves, Some(10))
+}
+
+pub fn part2(elves: &HashSet<Point>, engine: Engine) -> usize {
+    simulate(engine, elves, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitset_matches_hashset() {
+        let example = [
+            "....#..", "..###.#", "#...##.", ".#...##", "#.###..", "##.#.##", ".#..#..",
+        ]
+        .map(str::to_string);
+        let clump = [
+            "#.#..##...",
+            "..##.#.#.#",
+            "#....###..",
+            ".##.#...##",
+            "...#.##.#.",
+            "#.#...#..#",
+            ".###..#.#.",
+            "#..#.#...#",
+            "..#..##.#.",
+            ".#.#....##",
+        ]
+        .map(str::to_string);
+        // Wider than a word, so elves move across word boundaries
+        let wide = [
+            ".#..##.#.#...##.#",
+            "##.#..#...###.#..",
+            "..#.##..#.#..#.##",
+        ]
+        .map(|row| row.repeat(5));
+        let line = ["#.##..#...#.##.....#".repeat(7)];
+        let cases = [
+            example.to_vec(),
+            clump.to_vec(),
+            wide.to_vec(),
+            line.to_vec(),
+        ];
+        for lines in cases {
+            let elves = parse(&lines);
+            for rounds in [Some(1), Some(2), Some(5), Some(10), Some(25), None] {
+                assert_eq!(
+                    simulate(Engine::Bitset, &elves, rounds),
+                    simulate(Engine::HashSet, &elves, rounds),
+                    "{:?} rounds of {:?}",
+                    rounds,
+                    lines
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,32 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day23::{parse, part1, part2, Engine};
+
+fn main() {
+    if !(2..=3).contains(&env::args().count()) {
+        return println!(
+            "Usage: {} [path/to/input_file] [hashset|bitset]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let elves = parse(&lines);
+    //println!("Elves: {:?}", elves);
+
+    let engine = match env::args().nth(2).as_deref() {
+        None | Some("bitset") => Engine::Bitset,
+        Some("hashset") => Engine::HashSet,
+        Some(other) => panic!("Unknown engine {}", other),
+    };
+
+    println!("Part 1: {}", part1(&elves, engine));
+    println!("Part 2: {}", part2(&elves, engine));
+}
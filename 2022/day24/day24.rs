@@ -1,10 +1,8 @@
-use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-enum Direction {
+pub enum Direction {
     Right = 0,
     Left = 1,
     Up = 2,
@@ -13,7 +11,7 @@ enum Direction {
 }
 
 impl Direction {
-    fn from_isize(v: isize) -> Direction {
+    pub fn from_isize(v: isize) -> Direction {
         match v {
             0 => Direction::Right,
             1 => Direction::Left,
@@ -23,7 +21,7 @@ impl Direction {
             _ => todo!("Can't parse {} as Direction", v),
         }
     }
-    fn from_char(c: char) -> Direction {
+    pub fn from_char(c: char) -> Direction {
         match c {
             '>' => Direction::Right,
             '<' => Direction::Left,
@@ -32,7 +30,7 @@ impl Direction {
             _ => todo!("Direction char not implemented: {}", c),
         }
     }
-    fn to_char(&self) -> char {
+    pub fn to_char(&self) -> char {
         match self {
             Direction::Right => '>',
             Direction::Left => '<',
@@ -42,7 +40,7 @@ impl Direction {
         }
     }
 
-    fn to_vec2(&self) -> (isize, isize) {
+    pub fn to_vec2(&self) -> (isize, isize) {
         match &self {
             Direction::Left => (-1, 0),
             Direction::Right => (1, 0),
@@ -53,19 +51,19 @@ impl Direction {
     }
 }
 
-type Point = (isize, isize);
+pub type Point = (isize, isize);
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct Blizzard {
-    dir: Direction,
-    pos: Point,
+pub struct Blizzard {
+    pub dir: Direction,
+    pub pos: Point,
 }
 
-fn add(p1: Point, p2: Point) -> Point {
+pub fn add(p1: Point, p2: Point) -> Point {
     (p1.0 + p2.0, p1.1 + p2.1)
 }
 
-fn print_grid(blizzards: &Vec<Blizzard>, wh: Point) {
+pub fn print_grid(blizzards: &[Blizzard], wh: Point) {
     let (width, height) = wh;
     for y in 0..height {
         print!("#");
@@ -74,7 +72,7 @@ fn print_grid(blizzards: &Vec<Blizzard>, wh: Point) {
                 .iter()
                 .filter(|blizzard| blizzard.pos == (x, y))
                 .collect::<Vec<_>>();
-            if pos_blizzards.len() == 0 {
+            if pos_blizzards.is_empty() {
                 print!(".");
             } else if pos_blizzards.len() == 1 {
                 print!("{}", pos_blizzards[0].dir.to_char());
@@ -86,10 +84,10 @@ fn print_grid(blizzards: &Vec<Blizzard>, wh: Point) {
         }
         println!("#");
     }
-    println!("");
+    println!();
 }
 
-fn parse(lines: &Vec<String>) -> (Vec<Blizzard>, Point) {
+pub fn parse(lines: &[String]) -> (Vec<Blizzard>, Point) {
     let height = lines.len() - 2; // Remove the border walls
     let width = lines[0].len() - 2;
     (
@@ -119,111 +117,198 @@ fn parse(lines: &Vec<String>) -> (Vec<Blizzard>, Point) {
     )
 }
 
-static DEBUG: bool = false;
+impl Blizzard {
+    pub fn at(&self, minute: usize, wh: Point) -> Point {
+        let (dx, dy) = self.dir.to_vec2();
+        (
+            (self.pos.0 + dx * minute as isize).rem_euclid(wh.0),
+            (self.pos.1 + dy * minute as isize).rem_euclid(wh.1),
+        )
+    }
+}
 
-fn path_through_blizzard(
-    mut blizzards: Vec<Blizzard>,
-    wh: Point,
-    destinations: Vec<Point>,
-) -> usize {
-    let (width, height) = wh;
-    let entrance: Point = (0, -1);
-    let exit: Point = (width - 1, height);
-
-    let mut i_destination = 0;
-    let mut destination = destinations[i_destination];
-    let mut paths: HashSet<Point> = [entrance].into_iter().collect();
-
-    let direction_vecs = (0..Direction::COUNT as isize)
-        .into_iter()
-        .map(|dir| Direction::from_isize(dir).to_vec2())
-        .collect::<Vec<_>>();
-
-    for minute in 1..=usize::MAX {
-        if DEBUG {
-            println!("");
-            print_grid(&blizzards, wh);
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// Why the entrance or exit couldn't be found in a wall row
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GapError {
+    NoGap { wall: &'static str },
+    SeveralGaps { wall: &'static str, gaps: usize },
+}
+
+impl fmt::Display for GapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GapError::NoGap { wall } => write!(f, "There's no gap in the {} wall", wall),
+            GapError::SeveralGaps { wall, gaps } => {
+                write!(
+                    f,
+                    "There are {} gaps in the {} wall instead of one",
+                    gaps, wall
+                )
+            }
         }
+    }
+}
 
-        let blizzard_poss = blizzards
-            .iter_mut()
-            .map(|blizzard| {
-                blizzard.pos = add(blizzard.pos, blizzard.dir.to_vec2());
-                blizzard.pos.0 = blizzard.pos.0.rem_euclid(width);
-                blizzard.pos.1 = blizzard.pos.1.rem_euclid(height);
-                blizzard.pos
-            })
-            .collect::<HashSet<_>>();
+// The column of the single gap in a wall row, with the same x as the valley
+// cell below or above it. The corners are always wall.
+fn find_gap(row: &str, width: isize, wall: &'static str) -> Result<isize, GapError> {
+    let gaps: Vec<isize> = row
+        .chars()
+        .enumerate()
+        .filter(|&(x, c)| c == '.' && (1..=width as usize).contains(&x))
+        .map(|(x, _)| x as isize - 1)
+        .collect();
+    match gaps[..] {
+        [x] => Ok(x),
+        [] => Err(GapError::NoGap { wall }),
+        _ => Err(GapError::SeveralGaps {
+            wall,
+            gaps: gaps.len(),
+        }),
+    }
+}
 
-        let new_paths = paths
-            .iter()
-            .map(|pos| {
-                direction_vecs
-                    .iter()
-                    .map(|dir| add(*pos, *dir))
-                    .filter(|pos| {
-                        (pos.0 >= 0 && pos.0 < width && pos.1 >= 0 && pos.1 < height)
-                            || *pos == entrance
-                            || *pos == exit
-                    })
-                    .filter(|pos| !blizzard_poss.contains(&pos))
-                    .filter(|pos| !paths.contains(&pos))
+// Which cells have a blizzard in them, for every minute of the pattern.
+// Blizzards wrap around the valley, so the pattern repeats every
+// lcm(width, height) minutes.
+pub struct Valley {
+    pub width: isize,
+    pub height: isize,
+    pub entrance: Point,
+    pub exit: Point,
+    pub period: usize,
+    // One bitset per minute of the period, a bit per cell in row order
+    occupied: Vec<Vec<u64>>,
+}
+
+// Where someone is and how far along the waypoints they've got
+type State = (Point, usize, usize);
+
+impl Valley {
+    pub fn new(lines: &[String]) -> Result<Valley, GapError> {
+        let (blizzards, wh) = parse(lines);
+        let (width, height) = wh;
+        let entrance = (find_gap(&lines[0], width, "top")?, -1);
+        let exit = (
+            find_gap(&lines[height as usize + 1], width, "bottom")?,
+            height,
+        );
+        let period = width as usize / gcd(width as usize, height as usize) * height as usize;
+        let words = (width * height) as usize / 64 + 1;
+        let occupied = (0..period)
+            .map(|minute| {
+                let mut bits = vec![0u64; words];
+                for blizzard in &blizzards {
+                    let (x, y) = blizzard.at(minute, wh);
+                    let i = (y * width + x) as usize;
+                    bits[i / 64] |= 1 << (i % 64);
+                }
+                bits
             })
-            .flatten()
-            .collect::<HashSet<_>>();
+            .collect();
+        Ok(Valley {
+            width,
+            height,
+            entrance,
+            exit,
+            period,
+            occupied,
+        })
+    }
 
-        paths.extend(new_paths);
+    pub fn in_valley(&self, pos: Point) -> bool {
+        (0..self.width).contains(&pos.0) && (0..self.height).contains(&pos.1)
+            || pos == self.entrance
+            || pos == self.exit
+    }
 
-        // Remove paths that have a blizzard on them
-        paths.retain(|pos| !blizzard_poss.contains(&pos));
+    // Whether pos can be stood on at the given minute
+    pub fn is_free(&self, pos: Point, minute: usize) -> bool {
+        if pos == self.entrance || pos == self.exit {
+            return true;
+        }
+        if !self.in_valley(pos) {
+            return false;
+        }
+        let i = (pos.1 * self.width + pos.0) as usize;
+        self.occupied[minute % self.period][i / 64] & (1 << (i % 64)) == 0
+    }
 
-        if DEBUG {
-            println!("Minute {}: {} paths", minute, paths.len());
+    // The quickest route that starts at the first waypoint at the given
+    // minute and visits the rest in order, as the position at every minute
+    // from then on. None if the waypoints can't be reached.
+    pub fn itinerary(&self, waypoints: &[Point], start_minute: usize) -> Option<Vec<Point>> {
+        let (&start, rest) = waypoints.split_first()?;
+        if !self.is_free(start, start_minute) {
+            return None;
         }
+        let moves = [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)];
 
-        if paths.contains(&destination) {
-            paths = [destination].into_iter().collect();
-            i_destination += 1;
-            if i_destination == destinations.len() {
-                return minute;
+        // States only differ by the minute modulo the period, so each is
+        // only worth reaching once, and breadth first reaches it earliest
+        let first: State = (start, start_minute % self.period, 0);
+        let mut parents: HashMap<State, State> = HashMap::new();
+        let mut frontier = vec![first];
+        let mut minute = start_minute;
+        let mut end = None;
+        if rest.is_empty() {
+            end = Some(first);
+        }
+        'search: while end.is_none() && !frontier.is_empty() {
+            minute += 1;
+            let mut next_frontier = Vec::new();
+            for &state @ (pos, _, reached) in &frontier {
+                for step in moves {
+                    let next_pos = add(pos, step);
+                    if !self.is_free(next_pos, minute) {
+                        continue;
+                    }
+                    let next_reached = reached + (next_pos == rest[reached]) as usize;
+                    let next: State = (next_pos, minute % self.period, next_reached);
+                    if next == first || parents.contains_key(&next) {
+                        continue;
+                    }
+                    parents.insert(next, state);
+                    if next_reached == rest.len() {
+                        end = Some(next);
+                        break 'search;
+                    }
+                    next_frontier.push(next);
+                }
             }
-            destination = destinations[i_destination];
+            frontier = next_frontier;
         }
-    }
-    0
-}
 
-fn part1(blizzards: &Vec<Blizzard>, wh: Point) -> usize {
-    let (width, height) = wh;
-    let exit: Point = (width - 1, height);
-
-    path_through_blizzard(blizzards.clone(), wh, vec![exit])
+        let mut route = vec![end?.0];
+        let mut state = end?;
+        while state != first {
+            state = parents[&state];
+            route.push(state.0);
+        }
+        route.reverse();
+        Some(route)
+    }
 }
 
-fn part2(blizzards: &Vec<Blizzard>, wh: Point) -> usize {
-    let (width, height) = wh;
-    let entrance: Point = (0, -1);
-    let exit: Point = (width - 1, height);
-
-    path_through_blizzard(blizzards.clone(), wh, vec![exit, entrance, exit])
+pub fn part1(valley: &Valley) -> Vec<Point> {
+    valley
+        .itinerary(&[valley.entrance, valley.exit], 0)
+        .expect("Couldn't find a way through the valley")
 }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    let (parsed, wh) = parse(&lines);
-
-    println!("Part 1: {}", part1(&parsed, wh));
-    println!("Part 2: {}", part2(&parsed, wh));
+pub fn part2(valley: &Valley) -> Vec<Point> {
+    valley
+        .itinerary(
+            &[valley.entrance, valley.exit, valley.entrance, valley.exit],
+            0,
+        )
+        .expect("Couldn't find a way through the valley and back")
 }
@@ -0,0 +1,52 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::process;
+
+use day24::{part1, part2, Point, Valley};
+
+fn print_route(route: &[Point]) {
+    for (minute, step) in route.windows(2).enumerate() {
+        let (from, to) = (step[0], step[1]);
+        if from == to {
+            eprintln!("Minute {}: wait at {},{}", minute + 1, to.0, to.1);
+        } else {
+            eprintln!("Minute {}: move to {},{}", minute + 1, to.0, to.1);
+        }
+    }
+}
+
+fn main() {
+    if !(2..=3).contains(&env::args().count()) {
+        return println!(
+            "Usage: {} [path/to/input_file] [route]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    let valley = Valley::new(&lines).unwrap_or_else(|err| {
+        eprintln!("Couldn't read the valley: {}", err);
+        process::exit(1);
+    });
+
+    // `route` prints where to be every minute
+    let show_route = env::args().nth(2).is_some_and(|arg| arg == "route");
+
+    let route = part1(&valley);
+    if show_route {
+        print_route(&route);
+    }
+    println!("Part 1: {}", route.len() - 1);
+
+    let route = part2(&valley);
+    if show_route {
+        print_route(&route);
+    }
+    println!("Part 2: {}", route.len() - 1);
+}
@@ -1,108 +1,328 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
 
-use once_cell::sync::Lazy;
+// A whole number in a balanced base, where an odd base N has the digits
+// -N/2..=N/2. Digits are stored least significant first and with no
+// leading zeros, so every number has exactly one representation and can
+// grow as large as it needs to.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BalancedBase<const N: usize> {
+    digits: Vec<i8>,
+}
 
-static MAX_PLACE: usize = 25;
+pub type Snafu = BalancedBase<5>;
+pub type BalancedTernary = BalancedBase<3>;
 
-static PLACE_VALUES: Lazy<Vec<isize>> = Lazy::new(|| {
-    (0..=MAX_PLACE)
-        .into_iter()
-        .map(|place| 5isize.pow(place as u32))
-        .collect()
-});
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseDigitError {
+    pub symbol: char,
+    pub pos: usize,
+}
 
-static PLACE_MAXES: Lazy<Vec<isize>> = Lazy::new(|| {
-    (0..=MAX_PLACE)
-        .into_iter()
-        .map(|place| (0..=place).map(|i| 5isize.pow(i as u32) * 2).sum())
-        .collect()
-});
+impl fmt::Display for ParseDigitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Unknown digit {:?} at position {}",
+            self.symbol, self.pos
+        )
+    }
+}
+
+impl<const N: usize> BalancedBase<N> {
+    const HALF: i64 = (N / 2) as i64;
+    // Checked when a number is first made in a base, which stops the build
+    // for one that doesn't work. Digits are i8s, so N/2 can be at most 127.
+    const VALID: () = assert!(
+        N % 2 == 1 && N > 1 && N <= 255,
+        "Balanced bases have to be odd and from 3 to 255"
+    );
+
+    // Carries any column values, least significant first, into digits
+    fn from_columns(columns: impl IntoIterator<Item = i64>) -> Self {
+        let () = Self::VALID;
+        let mut digits = Vec::new();
+        let mut carry = 0;
+        let mut columns = columns.into_iter();
+        loop {
+            let value = match columns.next() {
+                Some(column) => column + carry,
+                None if carry != 0 => carry,
+                None => break,
+            };
+            let digit = (value + Self::HALF).rem_euclid(N as i64) - Self::HALF;
+            carry = (value - digit) / N as i64;
+            digits.push(digit as i8);
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BalancedBase { digits }
+    }
+
+    pub fn zero() -> Self {
+        BalancedBase { digits: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // Digits from the least significant one up
+    pub fn digits(&self) -> &[i8] {
+        &self.digits
+    }
+
+    // None if the number doesn't fit
+    pub fn to_i128(&self) -> Option<i128> {
+        self.digits.iter().rev().try_fold(0i128, |acc, &digit| {
+            acc.checked_mul(N as i128)?.checked_add(digit as i128)
+        })
+    }
+
+    pub fn parse_with(s: &str, symbols: &[char]) -> Result<Self, ParseDigitError> {
+        assert_eq!(symbols.len(), N, "Need one symbol per digit");
+        let columns = s
+            .chars()
+            .enumerate()
+            .map(|(pos, symbol)| {
+                symbols
+                    .iter()
+                    .position(|&other| other == symbol)
+                    .map(|i| i as i64 - Self::HALF)
+                    .ok_or(ParseDigitError { symbol, pos })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_columns(columns.into_iter().rev()))
+    }
+
+    pub fn format_with(&self, symbols: &[char]) -> String {
+        assert_eq!(symbols.len(), N, "Need one symbol per digit");
+        if self.is_zero() {
+            return symbols[Self::HALF as usize].to_string();
+        }
+        self.digits
+            .iter()
+            .rev()
+            .map(|&digit| symbols[(digit as i64 + Self::HALF) as usize])
+            .collect()
+    }
+}
+
+impl<const N: usize> From<i128> for BalancedBase<N> {
+    fn from(mut num: i128) -> Self {
+        let half = Self::HALF as i128;
+        let mut columns = Vec::new();
+        while num != 0 {
+            let digit = (num + half).rem_euclid(N as i128) - half;
+            num = (num - digit) / N as i128;
+            columns.push(digit as i64);
+        }
+        Self::from_columns(columns)
+    }
+}
+
+// Parsing and printing without giving symbols is only there for the bases
+// that have well known ones, listed from the digit -N/2 up to N/2. Any other
+// base has to use parse_with and format_with.
+macro_rules! default_symbols {
+    ($n:literal, $symbols:expr) => {
+        impl FromStr for BalancedBase<$n> {
+            type Err = ParseDigitError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_with(s, &$symbols)
+            }
+        }
+
+        impl fmt::Display for BalancedBase<$n> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.format_with(&$symbols))
+            }
+        }
+    };
+}
+default_symbols!(3, ['-', '0', '+']);
+default_symbols!(5, ['=', '-', '0', '1', '2']);
+
+impl<const N: usize> Add for &BalancedBase<N> {
+    type Output = BalancedBase<N>;
+    fn add(self, other: Self) -> BalancedBase<N> {
+        let len = self.digits.len().max(other.digits.len());
+        BalancedBase::from_columns((0..len).map(|i| {
+            *self.digits.get(i).unwrap_or(&0) as i64 + *other.digits.get(i).unwrap_or(&0) as i64
+        }))
+    }
+}
+
+impl<const N: usize> Neg for &BalancedBase<N> {
+    type Output = BalancedBase<N>;
+    fn neg(self) -> BalancedBase<N> {
+        BalancedBase {
+            digits: self.digits.iter().map(|digit| -digit).collect(),
+        }
+    }
+}
+
+impl<const N: usize> Sub for &BalancedBase<N> {
+    type Output = BalancedBase<N>;
+    fn sub(self, other: Self) -> BalancedBase<N> {
+        self + &-other
+    }
+}
+
+impl<const N: usize> Mul for &BalancedBase<N> {
+    type Output = BalancedBase<N>;
+    fn mul(self, other: Self) -> BalancedBase<N> {
+        if self.is_zero() || other.is_zero() {
+            return BalancedBase::zero();
+        }
+        let mut columns = vec![0i64; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            for (j, &b) in other.digits.iter().enumerate() {
+                columns[i + j] += a as i64 * b as i64;
+            }
+        }
+        BalancedBase::from_columns(columns)
+    }
+}
+
+macro_rules! forward_owned_op {
+    ($trait:ident, $method:ident) => {
+        impl<const N: usize> $trait for BalancedBase<N> {
+            type Output = BalancedBase<N>;
+            fn $method(self, other: Self) -> BalancedBase<N> {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+forward_owned_op!(Add, add);
+forward_owned_op!(Sub, sub);
+forward_owned_op!(Mul, mul);
+
+impl<const N: usize> Neg for BalancedBase<N> {
+    type Output = BalancedBase<N>;
+    fn neg(self) -> BalancedBase<N> {
+        -&self
+    }
+}
 
-fn parse(lines: &Vec<String>) -> Vec<isize> {
+impl<'a, const N: usize> Sum<&'a BalancedBase<N>> for BalancedBase<N> {
+    fn sum<I: Iterator<Item = &'a BalancedBase<N>>>(iter: I) -> Self {
+        iter.fold(BalancedBase::zero(), |acc, num| &acc + num)
+    }
+}
+
+pub fn parse(lines: &[String]) -> Vec<Snafu> {
     lines
         .iter()
         .map(|line| {
-            line.chars()
-                .rev()
-                .enumerate()
-                .map(|(place, c)| {
-                    PLACE_VALUES[place]
-                        * match c {
-                            '-' => -1isize,
-                            '=' => -2isize,
-                            '0'..='2' => c as isize - '0' as isize,
-                            _ => panic!("Couldn't parse character {}", c),
-                        }
-                })
-                .sum()
+            line.parse()
+                .unwrap_or_else(|err| panic!("Couldn't parse {}: {}", line, err))
         })
         .collect()
 }
 
-fn to_snafu(num: isize) -> String {
-    let num_len = PLACE_MAXES
-        .iter()
-        .position(|place_max| num <= *place_max)
-        .expect("Couldn't get size of number")
-        + 1;
-
-    static DIGITS: [&str; 5] = ["=", "-", "0", "1", "2"];
-
-    (0..num_len)
-        .into_iter()
-        .rev()
-        .fold(("".to_string(), 0isize), |(mut s, mut acc), place| {
-            let remaining = num - acc;
-
-            let digit = if place > 0 {
-                (-2..=2)
-                    .find(|digit| {
-                        ((digit * PLACE_VALUES[place] - PLACE_MAXES[place - 1])
-                            ..=(digit * PLACE_VALUES[place] + PLACE_MAXES[place - 1]))
-                            .contains(&remaining)
-                    })
-                    .expect("Couldn't get digit for place")
-            } else {
-                remaining
-            };
-            debug_assert!(digit >= -2 && digit <= 2);
-            acc += digit * PLACE_VALUES[place];
-            s += DIGITS[(digit + 2) as usize];
-            (s, acc)
-        })
-        .0
+pub fn part1(parsed: &[Snafu]) -> String {
+    let total_fuel: Snafu = parsed.iter().sum();
+    total_fuel.to_string()
 }
 
-fn part1(parsed: &Vec<isize>) -> String {
-    let total_fuel = parsed.iter().sum::<isize>();
-    to_snafu(total_fuel)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-static DEBUG: bool = false;
+    // Every small number, then larger ones of both signs including the
+    // biggest with 20 balanced ternary or SNAFU digits and the extremes that
+    // still fit in an i64
+    fn numbers() -> Vec<i128> {
+        let mut numbers: Vec<i128> = (-130..=130).collect();
+        numbers.extend([
+            2022,
+            -4890,
+            314_159,
+            -271_828,
+            1_743_392_200,
+            -1_743_392_201,
+            12_345_678_901,
+            -47_683_715_820_312,
+            47_683_715_820_313,
+            0,
+            i64::MAX as i128,
+            -7,
+            i64::MIN as i128,
+            i64::MIN as i128 + 1,
+        ]);
+        numbers
+    }
 
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
+    fn check_against_i128<const N: usize>()
+    where
+        BalancedBase<N>: FromStr + fmt::Display,
+        <BalancedBase<N> as FromStr>::Err: fmt::Debug,
+    {
+        let numbers = numbers();
+        for &a in &numbers {
+            let num = BalancedBase::<N>::from(a);
+            assert_eq!(num.to_i128(), Some(a), "{} in base {}", a, N);
+            assert_eq!(num.to_string().parse::<BalancedBase<N>>().unwrap(), num);
+            assert_eq!((-&num).to_i128(), Some(-a));
+        }
+        for pair in numbers.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (x, y) = (BalancedBase::<N>::from(a), BalancedBase::<N>::from(b));
+            assert_eq!((&x + &y).to_i128(), Some(a + b), "{} + {}", a, b);
+            assert_eq!((&x - &y).to_i128(), Some(a - b), "{} - {}", a, b);
+            assert_eq!((&x * &y).to_i128(), Some(a * b), "{} * {}", a, b);
+        }
+    }
+
+    #[test]
+    fn snafu_matches_i128() {
+        check_against_i128::<5>();
     }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
 
-    let parsed = parse(&lines);
-    if DEBUG {
-        parsed.iter().for_each(|num| {
-            println!("{} -> {}", num, to_snafu(*num));
-        });
-        println!("{:?}", parsed);
+    #[test]
+    fn balanced_ternary_matches_i128() {
+        check_against_i128::<3>();
     }
 
-    println!("Part 1: {}", part1(&parsed));
+    #[test]
+    fn balanced_ternary_carries() {
+        let ternary = |s: &str| s.parse::<BalancedTernary>().unwrap();
+        // 1 + 1 = 3 - 1
+        assert_eq!(ternary("+") + ternary("+"), ternary("+-"));
+        // 4 + 1 = 9 - 3 - 1, carrying twice
+        assert_eq!(ternary("++") + ternary("+"), ternary("+--"));
+        // -4 - 1 = -9 + 3 + 1
+        assert_eq!(ternary("--") - ternary("+"), ternary("-++"));
+        // 4 * 4 = 27 - 9 - 3 + 1
+        assert_eq!(ternary("++") * ternary("++"), ternary("+--+"));
+        // Carries that cancel out leave no leading zeros
+        assert_eq!(ternary("+-") + ternary("-+"), BalancedTernary::zero());
+        assert_eq!(ternary("+--+").to_i128(), Some(16));
+        assert_eq!(ternary("+--+").digits(), &[1, -1, -1, 1]);
+    }
+
+    #[test]
+    fn example_snafu() {
+        for (decimal, snafu) in [
+            (0, "0"),
+            (3, "1="),
+            (2022, "1=11-2"),
+            (314159265, "1121-1110-1=0"),
+        ] {
+            assert_eq!(Snafu::from(decimal).to_string(), snafu);
+            assert_eq!(snafu.parse::<Snafu>().unwrap().to_i128(), Some(decimal));
+        }
+        assert_eq!(
+            "1-3".parse::<Snafu>(),
+            Err(ParseDigitError {
+                symbol: '3',
+                pos: 2
+            })
+        );
+    }
 }
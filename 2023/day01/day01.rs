@@ -1,13 +1,10 @@
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-fn digits_to_value(num1: char, num2: char) -> usize {
+pub fn digits_to_value(num1: char, num2: char) -> usize {
     [num1, num2]
         .iter()
         .collect::<String>()
@@ -15,7 +12,7 @@ fn digits_to_value(num1: char, num2: char) -> usize {
         .unwrap()
 }
 
-fn part1(lines: &Vec<String>) -> usize {
+pub fn part1(lines: &[String]) -> usize {
     lines
         .iter()
         .map(|line| {
@@ -44,23 +41,22 @@ static DIGIT_TO_STR: Lazy<HashMap<&str, u8>> = Lazy::new(|| {
     m
 });
 
-fn parse_digit(s: &str) -> char {
+pub fn parse_digit(s: &str) -> char {
     (if let Some(v) = DIGIT_TO_STR.get(s) {
         *v
     } else {
         s.parse::<u8>().expect("Couldn't parse number")
-    } + '0' as u8) as char
+    } + b'0') as char
 }
 
-fn part2(lines: &Vec<String>) -> usize {
+pub fn part2(lines: &[String]) -> usize {
     static FORWARD_NUM_RE: Lazy<Regex> = Lazy::new(|| {
         Regex::new(
             DIGIT_TO_STR
                 .keys()
-                .map(|k| *k)
-                .chain(["[0-9]"].into_iter())
-                .intersperse("|")
-                .collect::<String>()
+                .copied()
+                .chain(["[0-9]"])
+                .join("|")
                 .as_str(),
         )
         .expect("Couldn't parse regex")
@@ -70,9 +66,8 @@ fn part2(lines: &Vec<String>) -> usize {
             DIGIT_TO_STR
                 .keys()
                 .map(|k| k.chars().rev().collect::<String>())
-                .chain([String::from("[0-9]")].into_iter())
-                .intersperse(String::from("|"))
-                .collect::<String>()
+                .chain([String::from("[0-9]")])
+                .join("|")
                 .as_str(),
         )
         .expect("Couldn't parse regex")
@@ -93,21 +88,3 @@ fn part2(lines: &Vec<String>) -> usize {
         })
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
-}
@@ -1,19 +1,16 @@
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::str::FromStr;
 
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Color {
+pub enum Color {
     Blue,
     Red,
     Green,
 }
 #[derive(Debug, PartialEq, Eq)]
-struct ParseColorErr;
+pub struct ParseColorErr;
 impl FromStr for Color {
     type Err = ParseColorErr;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -25,16 +22,16 @@ impl FromStr for Color {
         }
     }
 }
-type Round = HashMap<Color, usize>;
+pub type Round = HashMap<Color, usize>;
 
-struct Game {
-    id: usize,
-    rounds: Vec<Round>,
+pub struct Game {
+    pub id: usize,
+    pub rounds: Vec<Round>,
 }
 
-fn parse(lines: &Vec<String>) -> Vec<Game> {
+pub fn parse(lines: &[String]) -> Vec<Game> {
     lines
-        .into_iter()
+        .iter()
         .map(|line| {
             let id = line
                 .split(": ")
@@ -88,7 +85,7 @@ fn parse(lines: &Vec<String>) -> Vec<Game> {
         .collect()
 }
 
-fn part1(lines: &Vec<Game>) -> usize {
+pub fn part1(lines: &[Game]) -> usize {
     let cube_counts: HashMap<Color, usize> =
         HashMap::from([(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)]);
 
@@ -105,7 +102,7 @@ fn part1(lines: &Vec<Game>) -> usize {
         .sum()
 }
 
-fn min_of_color(game: &Game, color: Color) -> usize {
+pub fn min_of_color(game: &Game, color: Color) -> usize {
     game.rounds
         .iter()
         .map(|round| *round.get(&color).unwrap_or(&0))
@@ -113,7 +110,7 @@ fn min_of_color(game: &Game, color: Color) -> usize {
         .expect("Couldn't get max for game")
 }
 
-fn part2(lines: &Vec<Game>) -> usize {
+pub fn part2(lines: &[Game]) -> usize {
     lines
         .iter()
         .map(|game| {
@@ -123,22 +120,3 @@ fn part2(lines: &Vec<Game>) -> usize {
         })
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let parsed = parse(&lines);
-
-    println!("Part 1: {}", part1(&parsed));
-    println!("Part 2: {}", part2(&parsed));
-}
@@ -1,33 +1,22 @@
 use std::cmp::max;
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Point = (usize, usize);
+pub type Point = (usize, usize);
 #[derive(Debug)]
-struct Num {
-  pos: Point,
-  len: usize,
-  val: usize,
+pub struct Num {
+  pub pos: Point,
+  pub len: usize,
+  pub val: usize,
 }
-#[derive(Debug)]
-struct Board {
-  symbols: HashMap<Point, char>,
-  nums: Vec<Num>,
-}
-impl Default for Board {
-  fn default() -> Board {
-    Board {
-      symbols: HashMap::new(),
-      nums: vec![],
-    }
-  }
+#[derive(Debug, Default)]
+pub struct Board {
+  pub symbols: HashMap<Point, char>,
+  pub nums: Vec<Num>,
 }
 
-fn parse(lines: &Vec<String>) -> Board {
+pub fn parse(lines: &[String]) -> Board {
   lines
     .iter()
     .enumerate()
@@ -37,7 +26,7 @@ fn parse(lines: &Vec<String>) -> Board {
         .chain(['.']) // Finish numbers on the right
         .enumerate()
         .fold((Board::default(), None), |(board, cur_num), (x, char)| {
-          if char.is_digit(10) {
+          if char.is_ascii_digit() {
             (
               board,
               Some(cur_num.unwrap_or(String::from("")) + &char.to_string()),
@@ -79,7 +68,7 @@ fn parse(lines: &Vec<String>) -> Board {
     .expect("Couldn't parse board")
 }
 
-fn part1(board: &Board) -> usize {
+pub fn part1(board: &Board) -> usize {
   board
     .nums
     .iter()
@@ -92,7 +81,7 @@ fn part1(board: &Board) -> usize {
     .sum()
 }
 
-fn part2(board: &Board) -> usize {
+pub fn part2(board: &Board) -> usize {
   board
     .symbols
     .iter()
@@ -112,28 +101,3 @@ fn part2(board: &Board) -> usize {
     .map(|adjacent_nums| adjacent_nums.into_iter().product::<usize>())
     .sum()
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let board = parse(&lines);
-
-  // WRONG: 323955
-  // WRONG: 519922
-  // WRONG: 526868
-  // WRONG: 528547
-  // RIGHT! 521601
-  println!("Part 1: {}", part1(&board));
-  println!("Part 2: {}", part2(&board));
-}
@@ -1,13 +1,10 @@
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Game = (HashSet<usize>, HashSet<usize>);
+pub type Game = (HashSet<usize>, HashSet<usize>);
 
-fn parse(lines: &Vec<String>) -> Vec<Game> {
+pub fn parse(lines: &[String]) -> Vec<Game> {
   lines
     .iter()
     .map(|line| {
@@ -32,14 +29,14 @@ fn parse(lines: &Vec<String>) -> Vec<Game> {
     .collect()
 }
 
-fn score_games(games: &Vec<Game>) -> Vec<usize> {
+pub fn score_games(games: &[Game]) -> Vec<usize> {
   games
     .iter()
-    .map(|(winning, drawn)| drawn.intersection(&winning).count())
+    .map(|(winning, drawn)| drawn.intersection(winning).count())
     .collect()
 }
 
-fn part1(games: &Vec<Game>) -> usize {
+pub fn part1(games: &[Game]) -> usize {
   score_games(games)
     .into_iter()
     .filter(|&num_winning| num_winning != 0)
@@ -47,35 +44,13 @@ fn part1(games: &Vec<Game>) -> usize {
     .sum::<usize>()
 }
 
-fn part2(games: &Vec<Game>) -> usize {
-  let scored = score_games(&games);
+pub fn part2(games: &[Game]) -> usize {
+  let scored = score_games(games);
   let mut games_to_score: Vec<usize> = (0..games.len()).collect();
   let mut i = 0;
-  while games_to_score.len() > 0 {
-    let game = games_to_score
-      .pop()
-      .expect("Couldn't get top game to score");
+  while let Some(game) = games_to_score.pop() {
     games_to_score.extend(game + 1..=(game + scored[game]));
-    i = i + 1;
+    i += 1;
   }
   i
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-  let parsed = parse(&lines);
-
-  println!("Part 1: {}", part1(&parsed));
-  println!("Part 2: {}", part2(&parsed));
-}
@@ -1,15 +1,11 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use itertools::Itertools;
 
-type StartSeeds = Vec<usize>;
-type StartRangeSeeds = Vec<(usize, usize)>;
-type Mapping = (usize, usize, usize);
-type SectionMap = Vec<Mapping>;
+pub type StartSeeds = Vec<usize>;
+pub type StartRangeSeeds = Vec<(usize, usize)>;
+pub type Mapping = (usize, usize, usize);
+pub type SectionMap = Vec<Mapping>;
 
-fn parse_maps(lines: &Vec<String>) -> Vec<SectionMap> {
+pub fn parse_maps(lines: &[String]) -> Vec<SectionMap> {
   lines
     .split(String::is_empty)
     .skip(1)
@@ -30,7 +26,7 @@ fn parse_maps(lines: &Vec<String>) -> Vec<SectionMap> {
     .collect()
 }
 
-fn parse_simple_seeds(lines: &Vec<String>) -> StartSeeds {
+pub fn parse_simple_seeds(lines: &[String]) -> StartSeeds {
   lines
     .split(String::is_empty)
     .take(1)
@@ -51,7 +47,7 @@ fn parse_simple_seeds(lines: &Vec<String>) -> StartSeeds {
     .collect()
 }
 
-fn map_seed_to_location(seed: usize, section_maps: &Vec<SectionMap>) -> usize {
+pub fn map_seed_to_location(seed: usize, section_maps: &[SectionMap]) -> usize {
   section_maps.iter().fold(seed, |cur_num, section_map| {
     if let Some((dest_range, source_range, _)) = section_map
       .iter()
@@ -64,7 +60,7 @@ fn map_seed_to_location(seed: usize, section_maps: &Vec<SectionMap>) -> usize {
   })
 }
 
-fn map_location_to_seed(location: usize, section_maps: &Vec<SectionMap>) -> usize {
+pub fn map_location_to_seed(location: usize, section_maps: &[SectionMap]) -> usize {
   section_maps
     .iter()
     .rev()
@@ -80,11 +76,11 @@ fn map_location_to_seed(location: usize, section_maps: &Vec<SectionMap>) -> usiz
     })
 }
 
-fn parse_range_seeds(lines: &Vec<String>) -> StartRangeSeeds {
+pub fn parse_range_seeds(lines: &[String]) -> StartRangeSeeds {
   parse_simple_seeds(lines).into_iter().tuples().collect()
 }
 
-fn part1(start_seeds: &StartSeeds, section_maps: &Vec<SectionMap>) -> usize {
+pub fn part1(start_seeds: &StartSeeds, section_maps: &[SectionMap]) -> usize {
   start_seeds
     .iter()
     .map(|&start_seed| map_seed_to_location(start_seed, section_maps))
@@ -92,7 +88,7 @@ fn part1(start_seeds: &StartSeeds, section_maps: &Vec<SectionMap>) -> usize {
     .expect("Couldn't get min location")
 }
 
-fn part2(start_seeds: &StartRangeSeeds, section_maps: &Vec<SectionMap>) -> usize {
+pub fn part2(start_seeds: &StartRangeSeeds, section_maps: &[SectionMap]) -> usize {
   (0..usize::MAX)
     .find(|&location_num| {
       let seed_for_loc = map_location_to_seed(location_num, section_maps);
@@ -126,25 +122,3 @@ fn part2(start_seeds: &StartRangeSeeds, section_maps: &Vec<SectionMap>) -> usize
     .expect("Couldn't find min seed")
     */
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let section_maps = parse_maps(&lines);
-  let start_seeds = parse_simple_seeds(&lines);
-  let range_seeds = parse_range_seeds(&lines);
-
-  println!("Part 1: {}", part1(&start_seeds, &section_maps));
-  println!("Part 2: {}", part2(&range_seeds, &section_maps));
-}
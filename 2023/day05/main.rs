@@ -0,0 +1,27 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day05::{parse_maps, parse_range_seeds, parse_simple_seeds, part1, part2};
+
+fn main() {
+  if env::args().count() != 2 {
+    return println!(
+      "Usage: {} [path/to/input_file]",
+      env::args().next().expect("Couldn't get executable name")
+    );
+  }
+  let input_name: String = env::args().nth(1).expect("First argument");
+  let f = File::open(input_name).expect("Couldn't open input file");
+  let lines: Vec<String> = io::BufReader::new(f)
+    .lines()
+    .map_while(Result::ok)
+    .collect();
+
+  let section_maps = parse_maps(&lines);
+  let start_seeds = parse_simple_seeds(&lines);
+  let range_seeds = parse_range_seeds(&lines);
+
+  println!("Part 1: {}", part1(&start_seeds, &section_maps));
+  println!("Part 2: {}", part2(&range_seeds, &section_maps));
+}
@@ -1,10 +1,6 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use itertools::Itertools;
 
-fn parse(lines: &Vec<String>) -> Vec<(usize, usize)> {
+pub fn parse(lines: &[String]) -> Vec<(usize, usize)> {
   let (times, distances) = lines
     .iter()
     .map(|line| {
@@ -23,7 +19,7 @@ fn parse(lines: &Vec<String>) -> Vec<(usize, usize)> {
   times.interleave(distances).tuples().collect()
 }
 
-fn part1(parsed: &Vec<(usize, usize)>) -> usize {
+pub fn part1(parsed: &[(usize, usize)]) -> usize {
   parsed
     .iter()
     .map(|(race_time, distance_record)| {
@@ -35,7 +31,7 @@ fn part1(parsed: &Vec<(usize, usize)>) -> usize {
     .product()
 }
 
-fn parse2(lines: &Vec<String>) -> (usize, usize) {
+pub fn parse2(lines: &[String]) -> (usize, usize) {
   lines
     .iter()
     .map(|line| {
@@ -53,7 +49,7 @@ fn parse2(lines: &Vec<String>) -> (usize, usize) {
     .expect("Couldn't find times and distances")
 }
 
-fn part2(parsed: &(usize, usize)) -> usize {
+pub fn part2(parsed: &(usize, usize)) -> usize {
   let &(race_time, distance_record) = parsed;
   (0..=race_time)
     .rev()
@@ -64,23 +60,3 @@ fn part2(parsed: &(usize, usize)) -> usize {
       .expect("Couldn't find start")
     + 1
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-  let parsed = parse(&lines);
-  let parsed2 = parse2(&lines);
-
-  println!("Part 1: {}", part1(&parsed));
-  println!("Part 2: {}", part2(&parsed2));
-}
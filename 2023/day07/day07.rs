@@ -1,14 +1,11 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::str::FromStr;
 
 use itertools::Itertools;
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
-enum Card {
+pub enum Card {
   One = 0,
   Two,
   Three,
@@ -26,7 +23,7 @@ enum Card {
 }
 
 impl Card {
-  fn from_char(c: char) -> Result<Card, ParseCardTypeError> {
+  pub fn from_char(c: char) -> Result<Card, ParseCardTypeError> {
     match c {
       '1' => Ok(Card::One),
       '2' => Ok(Card::Two),
@@ -48,7 +45,7 @@ impl Card {
 }
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
-struct ParseCardTypeError;
+pub struct ParseCardTypeError;
 impl FromStr for Card {
   type Err = ParseCardTypeError;
   fn from_str(s: &str) -> Result<Card, ParseCardTypeError> {
@@ -61,8 +58,8 @@ impl FromStr for Card {
   }
 }
 
-type Hand = Vec<Card>;
-fn parse(lines: &Vec<String>) -> Vec<(Hand, usize)> {
+pub type Hand = Vec<Card>;
+pub fn parse(lines: &[String]) -> Vec<(Hand, usize)> {
   lines
     .iter()
     .map(|line| {
@@ -89,7 +86,7 @@ fn parse(lines: &Vec<String>) -> Vec<(Hand, usize)> {
 }
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
-enum HandType {
+pub enum HandType {
   HighCard,
   OnePair,
   TwoPair,
@@ -99,7 +96,7 @@ enum HandType {
   FiveKind,
 }
 
-fn card_ordering_p2(card: Card) -> isize {
+pub fn card_ordering_p2(card: Card) -> isize {
   match card {
     Card::J => -1,
     _ => card as isize,
@@ -107,15 +104,15 @@ fn card_ordering_p2(card: Card) -> isize {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-enum Part {
+pub enum Part {
   P1,
   P2,
 }
-fn score_hand_type(hand: &Hand, part: &Part) -> Option<HandType> {
+pub fn score_hand_type(hand: &Hand, part: &Part) -> Option<HandType> {
   let hand_set: HashSet<_> = hand.iter().cloned().collect();
   let hand_map: HashMap<Card, usize> = hand_set
     .iter()
-    .map(|&card| (card, hand.into_iter().filter(|&c| *c == card).count()))
+    .map(|&card| (card, hand.iter().filter(|&c| *c == card).count()))
     .collect();
   if hand_set.len() == 1 {
     Some(HandType::FiveKind)
@@ -160,13 +157,13 @@ fn score_hand_type(hand: &Hand, part: &Part) -> Option<HandType> {
   }
 }
 
-fn cmp_hands(h1: &Hand, h2: &Hand, part: &Part) -> Ordering {
+pub fn cmp_hands(h1: &Hand, h2: &Hand, part: &Part) -> Ordering {
   let t1 = score_hand_type(h1, part).expect("couldn't score h1");
   let t2 = score_hand_type(h2, part).expect("couldn't score h2");
   if t1 == t2 {
     let (v1, v2) = h1
-      .into_iter()
-      .interleave(h2.into_iter())
+      .iter()
+      .interleave(h2)
       .tuples()
       .find(|(&c1, &c2)| c1 != c2)
       .expect("Couldn't find tie break difference between hands");
@@ -179,7 +176,7 @@ fn cmp_hands(h1: &Hand, h2: &Hand, part: &Part) -> Ordering {
   }
 }
 
-fn solve(hands: &Vec<(Hand, usize)>, part: Part) -> usize {
+pub fn solve(hands: &[(Hand, usize)], part: Part) -> usize {
   hands
     .iter()
     .sorted_by(|h1, h2| cmp_hands(&h1.0, &h2.0, &part))
@@ -187,28 +184,3 @@ fn solve(hands: &Vec<(Hand, usize)>, part: Part) -> usize {
     .map(|(rank, (_, bet))| (rank + 1) * bet)
     .sum()
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let parsed = parse(&lines);
-
-  // p1
-  // WRONG 251708371
-  // WRONG 250345569
-  // p2
-  // WRONG 252782999
-  println!("Part 1: {}", solve(&parsed, Part::P1));
-  println!("Part 2: {}", solve(&parsed, Part::P2));
-}
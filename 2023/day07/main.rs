@@ -0,0 +1,30 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day07::{parse, solve, Part};
+
+fn main() {
+  if env::args().count() != 2 {
+    return println!(
+      "Usage: {} [path/to/input_file]",
+      env::args().next().expect("Couldn't get executable name")
+    );
+  }
+  let input_name: String = env::args().nth(1).expect("First argument");
+  let f = File::open(input_name).expect("Couldn't open input file");
+  let lines: Vec<String> = io::BufReader::new(f)
+    .lines()
+    .map_while(Result::ok)
+    .collect();
+
+  let parsed = parse(&lines);
+
+  // p1
+  // WRONG 251708371
+  // WRONG 250345569
+  // p2
+  // WRONG 252782999
+  println!("Part 1: {}", solve(&parsed, Part::P1));
+  println!("Part 2: {}", solve(&parsed, Part::P2));
+}
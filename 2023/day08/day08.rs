@@ -1,18 +1,15 @@
-use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::collections::HashMap;
 
 use itertools::Itertools;
 
-fn parse(lines: &Vec<String>) -> (String, HashMap<String, (String, String)>) {
+pub fn parse(lines: &[String]) -> (String, HashMap<String, (String, String)>) {
   (
     lines
       .split(String::is_empty)
       .take(1)
       .exactly_one()
       .expect("Couldn't get directions")
-      .into_iter()
+      .iter()
       .take(1)
       .exactly_one()
       .expect("Couldn't get directions")
@@ -43,7 +40,7 @@ fn parse(lines: &Vec<String>) -> (String, HashMap<String, (String, String)>) {
             .strip_suffix(")")
             .expect("Couldn't strip closing paren")
             .split(", ")
-            .map(|s| String::from(s))
+            .map(String::from)
             .collect_tuple()
             .expect("Couldn't get tuple"),
         )
@@ -52,7 +49,7 @@ fn parse(lines: &Vec<String>) -> (String, HashMap<String, (String, String)>) {
   )
 }
 
-fn part1(directions: &String, nodes: &HashMap<String, (String, String)>) -> usize {
+pub fn part1(directions: &str, nodes: &HashMap<String, (String, String)>) -> usize {
   directions
     .chars()
     .cycle()
@@ -82,16 +79,20 @@ fn part1(directions: &String, nodes: &HashMap<String, (String, String)>) -> usiz
 }
 
 // returns the start of the cycle, its length, and the indices of any ends nodes along the cycle
-fn find_cycle_counts(
-  start_node: &String,
-  directions: &String,
+pub fn find_cycle_counts(
+  start_node: &str,
+  directions: &str,
   nodes: &HashMap<String, (String, String)>,
 ) -> usize {
   let steps = directions
     .chars()
     .cycle()
     .scan(
-      (start_node.clone(), HashMap::<String, usize>::new(), 0usize),
+      (
+        start_node.to_string(),
+        HashMap::<String, usize>::new(),
+        0usize,
+      ),
       |(cur_node, visited_nodes, i), cur_direction| {
         if cur_node.ends_with('Z') && visited_nodes.contains_key(cur_node) {
           None
@@ -118,7 +119,7 @@ fn find_cycle_counts(
   cycle_start_node.1
 }
 
-fn part2(directions: &String, nodes: &HashMap<String, (String, String)>) -> usize {
+pub fn part2(directions: &str, nodes: &HashMap<String, (String, String)>) -> usize {
   let cycle_lengths = nodes
     .keys()
     .filter(|node| node.ends_with('A'))
@@ -134,23 +135,3 @@ fn part2(directions: &String, nodes: &HashMap<String, (String, String)>) -> usiz
     .expect("Couldn't find cycle count")
     * shortest_cycle
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let (directions, nodes) = parse(&lines);
-
-  println!("Part 1: {}", part1(&directions, &nodes));
-  println!("Part 2: {}", part2(&directions, &nodes));
-}
@@ -1,12 +1,8 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use itertools::Itertools;
 
-type Sequence = Vec<isize>;
+pub type Sequence = Vec<isize>;
 
-fn parse(lines: &Vec<String>) -> Vec<Sequence> {
+pub fn parse(lines: &[String]) -> Vec<Sequence> {
   lines
     .iter()
     .map(|line| {
@@ -19,7 +15,7 @@ fn parse(lines: &Vec<String>) -> Vec<Sequence> {
     .collect()
 }
 
-fn extrapolate_sequence(sequence: &Sequence) -> isize {
+pub fn extrapolate_sequence(sequence: &Sequence) -> isize {
   let mut diffs = vec![sequence.clone()];
   loop {
     let next_diffs = diffs
@@ -40,7 +36,7 @@ fn extrapolate_sequence(sequence: &Sequence) -> isize {
   })
 }
 
-fn extrapolate_sequence_rev(sequence: &Sequence) -> isize {
+pub fn extrapolate_sequence_rev(sequence: &Sequence) -> isize {
   let mut diffs = vec![sequence.clone()];
   loop {
     let next_diffs = diffs
@@ -61,36 +57,10 @@ fn extrapolate_sequence_rev(sequence: &Sequence) -> isize {
   })
 }
 
-fn part1(sequences: &Vec<Sequence>) -> isize {
-  sequences
-    .iter()
-    .map(|sequence| extrapolate_sequence(sequence))
-    .sum()
-}
-
-fn part2(sequences: &Vec<Sequence>) -> isize {
-  sequences
-    .iter()
-    .map(|sequence| extrapolate_sequence_rev(sequence))
-    .sum()
+pub fn part1(sequences: &[Sequence]) -> isize {
+  sequences.iter().map(extrapolate_sequence).sum()
 }
 
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let parsed = parse(&lines);
-
-  println!("Part 1: {}", part1(&parsed));
-  println!("Part 2: {}", part2(&parsed));
+pub fn part2(sequences: &[Sequence]) -> isize {
+  sequences.iter().map(extrapolate_sequence_rev).sum()
 }
@@ -1,23 +1,19 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use indicatif::ProgressIterator;
 use itertools::Itertools;
 use pariter::{scope, IteratorExt as _};
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-enum Spring {
+pub enum Spring {
   Damaged,
   Operational,
   Unknown,
   ConsumedDamaged,
 }
-type Row = Vec<Spring>;
-type Counts = Vec<usize>;
-type Parsed = Vec<(Row, Counts)>;
+pub type Row = Vec<Spring>;
+pub type Counts = Vec<usize>;
+pub type Parsed = Vec<(Row, Counts)>;
 
-fn parse(lines: &Vec<String>) -> Parsed {
+pub fn parse(lines: &[String]) -> Parsed {
   lines
     .iter()
     .map(|line| {
@@ -46,12 +42,11 @@ fn parse(lines: &Vec<String>) -> Parsed {
     .collect()
 }
 
-fn solve_row(row: Row, counts: Counts) -> usize {
+pub fn solve_row(row: Row, counts: Counts) -> usize {
   let mut to_solve = vec![(row.clone(), counts)];
   let mut solved: Vec<Row> = vec![];
-  while !to_solve.is_empty() {
-    let (row, mut counts) = to_solve.pop().unwrap();
-    if counts.is_empty() && !row.contains(&&Spring::Damaged) {
+  while let Some((row, mut counts)) = to_solve.pop() {
+    if counts.is_empty() && !row.contains(&Spring::Damaged) {
       solved.push(row);
       continue;
     }
@@ -86,7 +81,7 @@ fn solve_row(row: Row, counts: Counts) -> usize {
           if let Some((damaged_ind, _)) = window.iter().find_position(|&x| *x == Spring::Damaged) {
             *least_damaged_ind = Some(i + damaged_ind);
           }
-          return Some((i, window));
+          Some((i, window))
         })
         .filter(|(_, window)| {
           window
@@ -110,7 +105,7 @@ fn solve_row(row: Row, counts: Counts) -> usize {
   solved.len()
 }
 
-fn part1(springs: &Parsed) -> usize {
+pub fn part1(springs: &Parsed) -> usize {
   springs
     .iter()
     .map(|(row, counts)| solve_row(row.clone(), counts.clone()))
@@ -118,21 +113,19 @@ fn part1(springs: &Parsed) -> usize {
     .sum()
 }
 
-fn part2(springs: &Parsed) -> usize {
+pub fn part2(springs: &Parsed) -> usize {
   scope(|scope| {
     springs
       .iter()
       .map(|(row, counts)| {
         (
           (0..5)
-            .map(|_| row.iter())
-            .flatten()
+            .flat_map(|_| row.iter())
             .to_owned()
             .copied()
             .collect(),
           (0..5)
-            .map(|_| counts.iter())
-            .flatten()
+            .flat_map(|_| counts.iter())
             .to_owned()
             .copied()
             .collect(),
@@ -145,26 +138,6 @@ fn part2(springs: &Parsed) -> usize {
   .unwrap()
 }
 
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-
-  let parsed = parse(&lines);
-
-  println!("Part 1: {}", part1(&parsed));
-  println!("Part 2: {}", part2(&parsed));
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,18 +1,15 @@
 use std::collections::{HashMap, HashSet};
-use std::env;
 use std::fmt::Debug;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
-enum Tile {
+pub enum Tile {
   Ash,
   Rocks,
 }
-type Parsed = Vec<Vec<Vec<Tile>>>;
-fn parse(lines: &Vec<String>) -> Parsed {
+pub type Parsed = Vec<Vec<Vec<Tile>>>;
+pub fn parse(lines: &[String]) -> Parsed {
   lines
     .split(String::is_empty)
     .map(|lines| {
@@ -32,7 +29,7 @@ fn parse(lines: &Vec<String>) -> Parsed {
     .collect()
 }
 
-trait Diff {
+pub trait Diff {
   fn diff(&self, s: &Self) -> usize;
 }
 impl Diff for Tile {
@@ -58,7 +55,7 @@ where
   }
 }
 
-fn find_splits<T: Eq + Debug + Diff>(vec: &Vec<T>) -> HashMap<usize, usize> {
+pub fn find_splits<T: Eq + Debug + Diff>(vec: &[T]) -> HashMap<usize, usize> {
   (1..vec.len())
     .map(|split_ind| {
       (
@@ -75,8 +72,8 @@ fn find_splits<T: Eq + Debug + Diff>(vec: &Vec<T>) -> HashMap<usize, usize> {
     .collect()
 }
 
-fn find_split<T: Eq + Debug + Diff>(vec: &Vec<T>, delta: usize) -> HashSet<usize> {
-  find_splits(&vec)
+pub fn find_split<T: Eq + Debug + Diff>(vec: &[T], delta: usize) -> HashSet<usize> {
+  find_splits(vec)
     .iter()
     .filter(|(_, count)| **count == delta)
     .map(|(split_ind, _)| split_ind)
@@ -84,7 +81,7 @@ fn find_split<T: Eq + Debug + Diff>(vec: &Vec<T>, delta: usize) -> HashSet<usize
     .collect::<HashSet<_>>()
 }
 
-fn transpose<T>(v: &Vec<Vec<T>>) -> Vec<Vec<T>>
+pub fn transpose<T>(v: &[Vec<T>]) -> Vec<Vec<T>>
 where
   T: Copy,
 {
@@ -94,12 +91,12 @@ where
     .collect()
 }
 
-fn solve(grids: &Parsed, delta: usize) -> usize {
+pub fn solve(grids: &Parsed, delta: usize) -> usize {
   grids
     .iter()
     .map(|grid| -> usize {
       let horizontal_splits = find_split(grid, delta);
-      let vertical_splits = find_split(&transpose(&grid), delta);
+      let vertical_splits = find_split(&transpose(grid), delta);
       (if let Ok(vertical_split) = vertical_splits.iter().take(1).exactly_one() {
         *vertical_split
       } else {
@@ -113,34 +110,13 @@ fn solve(grids: &Parsed, delta: usize) -> usize {
     .sum()
 }
 
-fn part1(grid: &Parsed) -> usize {
+pub fn part1(grid: &Parsed) -> usize {
   solve(grid, 0)
 }
-fn part2(grid: &Parsed) -> usize {
+pub fn part2(grid: &Parsed) -> usize {
   solve(grid, 1)
 }
 
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-  let parsed = parse(&lines);
-
-  println!("Part 1: {}", part1(&parsed));
-  // WRONG: 39114
-  // MAYBE? 3700
-  println!("Part 2: {}", part2(&parsed));
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,24 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day15::{parse, part1, part2};
+
+fn main() {
+  if env::args().count() != 2 {
+    return println!(
+      "Usage: {} [path/to/input_file]",
+      env::args().next().expect("Couldn't get executable name")
+    );
+  }
+  let input_name: String = env::args().nth(1).expect("First argument");
+  let f = File::open(input_name).expect("Couldn't open input file");
+  let lines: Vec<String> = io::BufReader::new(f)
+    .lines()
+    .map_while(Result::ok)
+    .collect();
+  let parsed = parse(&lines);
+
+  println!("Part 1: {}", part1(&parsed));
+  println!("Part 2: {}", part2(&parsed));
+}
@@ -1,15 +1,12 @@
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::mem::swap;
 use std::ops::Add;
 
 use itertools::Itertools;
 
 #[derive(Eq, PartialEq, Hash, Debug)]
-enum Tile {
+pub enum Tile {
   ForwardMirror,
   BackMirror,
   HSplitter,
@@ -17,24 +14,24 @@ enum Tile {
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
-struct Vec2 {
-  x: isize,
-  y: isize,
+pub struct Vec2 {
+  pub x: isize,
+  pub y: isize,
 }
 
 #[derive(Eq, PartialEq, Debug)]
-struct Board {
-  tiles: HashMap<Vec2, Tile>,
-  width: isize,
-  height: isize,
+pub struct Board {
+  pub tiles: HashMap<Vec2, Tile>,
+  pub width: isize,
+  pub height: isize,
 }
 
-fn parse(lines: &Vec<String>) -> Board {
+pub fn parse(lines: &[String]) -> Board {
   Board {
     tiles: lines
       .iter()
       .enumerate()
-      .map(|(y, line)| {
+      .flat_map(|(y, line)| {
         line.chars().enumerate().filter_map(move |(x, c)| {
           Some((
             Vec2 {
@@ -51,7 +48,6 @@ fn parse(lines: &Vec<String>) -> Board {
           ))
         })
       })
-      .flatten()
       .collect(),
     height: lines.len() as isize,
     width: lines.first().unwrap().len() as isize,
@@ -59,10 +55,10 @@ fn parse(lines: &Vec<String>) -> Board {
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
-struct Beam {
+pub struct Beam {
   //i: usize,
-  p: Vec2,
-  v: Vec2,
+  pub p: Vec2,
+  pub v: Vec2,
 }
 
 impl Add for Vec2 {
@@ -76,13 +72,13 @@ impl Add for Vec2 {
   }
 }
 
-fn display(board: &Board, energized: &HashSet<Vec2>) {
+pub fn display(board: &Board, energized: &HashSet<Vec2>) {
   println!(
     "{}",
     (0..board.width)
       .map(|y| (0..board.height)
         .map(|x| {
-          let p = Vec2 { x: x, y: y };
+          let p = Vec2 { x, y };
           if energized.contains(&p) {
             '#'
           } else {
@@ -97,12 +93,12 @@ fn display(board: &Board, energized: &HashSet<Vec2>) {
 static RIGHT: Vec2 = Vec2 { x: 1, y: 0 };
 static DOWN: Vec2 = Vec2 { x: 0, y: 1 };
 
-fn energized_from_start(board: &Board, start: Beam) -> usize {
+pub fn energized_from_start(board: &Board, start: Beam) -> usize {
   let mut energized = HashSet::<Beam>::new();
   let mut beams = vec![start];
-  while !beams.is_empty() {
+  while let Some(mut beam) = beams.pop() {
     //dbg!(&beams);
-    let mut beam = beams.pop().unwrap();
+
     beam.p = beam.p + beam.v;
     if !(0..board.width).contains(&beam.p.x) || !(0..board.height).contains(&beam.p.y) {
       continue;
@@ -130,7 +126,7 @@ fn energized_from_start(board: &Board, start: Beam) -> usize {
           if beam.v.x == 0 {
             beam.v = RIGHT;
             //beam.i *= 2;
-            beams.push(beam.clone());
+            beams.push(beam);
             //beam.i += 1;
             beam.v.x *= -1;
           }
@@ -139,7 +135,7 @@ fn energized_from_start(board: &Board, start: Beam) -> usize {
           //  |
           if beam.v.y == 0 {
             beam.v = DOWN;
-            beams.push(beam.clone());
+            beams.push(beam);
             beam.v.y *= -1;
           }
         }
@@ -164,7 +160,7 @@ fn energized_from_start(board: &Board, start: Beam) -> usize {
   energized.len()
 }
 
-fn part1(board: &Board) -> usize {
+pub fn part1(board: &Board) -> usize {
   energized_from_start(
     board,
     Beam {
@@ -175,9 +171,9 @@ fn part1(board: &Board) -> usize {
   )
 }
 
-fn part2(board: &Board) -> usize {
+pub fn part2(board: &Board) -> usize {
   (0..max(board.width, board.height))
-    .map(|pos_magnitude| {
+    .flat_map(|pos_magnitude| {
       let mut energized = vec![];
       let mut insert_beam = |beam: Beam| {
         energized.push(energized_from_start(board, beam));
@@ -210,27 +206,6 @@ fn part2(board: &Board) -> usize {
 
       energized
     })
-    .flatten()
-    .into_iter()
     .max()
     .unwrap()
 }
-
-fn main() {
-  if env::args().count() != 2 {
-    return println!(
-      "Usage: {} [path/to/input_file]",
-      env::args().next().expect("Couldn't get executable name")
-    );
-  }
-  let input_name: String = env::args().skip(1).next().expect("First argument");
-  let f = File::open(input_name).expect("Couldn't open input file");
-  let lines: Vec<String> = io::BufReader::new(f)
-    .lines()
-    .filter_map(Result::ok)
-    .collect();
-  let board = parse(&lines);
-
-  println!("Part 1: {}", part1(&board));
-  println!("Part 2: {}", part2(&board));
-}
@@ -1,10 +1,7 @@
-use itertools::{sorted, Itertools};
+use itertools::sorted;
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
-fn parse(lines: &Vec<String>) -> [Vec<usize>; 2] {
+pub fn parse(lines: &[String]) -> [Vec<usize>; 2] {
     lines
         .iter()
         .fold([vec![], vec![]], |vecs, line| {
@@ -19,7 +16,7 @@ fn parse(lines: &Vec<String>) -> [Vec<usize>; 2] {
         .map(|list| sorted(list).collect())
 }
 
-fn part1(lists: &[Vec<usize>; 2]) -> usize {
+pub fn part1(lists: &[Vec<usize>; 2]) -> usize {
     lists[0]
         .iter()
         .zip(lists[1].iter())
@@ -27,7 +24,7 @@ fn part1(lists: &[Vec<usize>; 2]) -> usize {
         .sum()
 }
 
-fn part2(lists: &[Vec<usize>; 2]) -> usize {
+pub fn part2(lists: &[Vec<usize>; 2]) -> usize {
     let counts = lists[1]
         .iter()
         .fold(HashMap::<usize, usize>::new(), |mut counts, x| {
@@ -39,22 +36,3 @@ fn part2(lists: &[Vec<usize>; 2]) -> usize {
         .map(|x| x * counts.get(x).unwrap_or(&0))
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let parsed = parse(&lines);
-
-    println!("Part 1: {}", part1(&parsed));
-    println!("Part 2: {}", part2(&parsed));
-}
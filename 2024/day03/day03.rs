@@ -1,16 +1,13 @@
 use once_cell::sync::Lazy;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use regex::Regex;
 
 static MUL_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap());
 
-type MulOp = (usize, usize);
+pub type MulOp = (usize, usize);
 
-fn parse(lines: &Vec<String>, always_enabled: bool) -> Vec<MulOp> {
+pub fn parse(lines: &[String], always_enabled: bool) -> Vec<MulOp> {
     let line = lines.join("");
     MUL_RE
         .captures_iter(line.as_str())
@@ -40,24 +37,6 @@ fn parse(lines: &Vec<String>, always_enabled: bool) -> Vec<MulOp> {
         .1
 }
 
-fn sum_ops(ops: &Vec<MulOp>) -> usize {
+pub fn sum_ops(ops: &[MulOp]) -> usize {
     ops.iter().map(|(x, y)| x * y).sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    println!("Part 1: {}", sum_ops(&parse(&lines, true)));
-    println!("Part 2: {}", sum_ops(&parse(&lines, false)));
-}
@@ -1,14 +1,10 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use itertools::Itertools;
 
-fn parse(lines: &Vec<String>) -> Vec<Vec<char>> {
+pub fn parse(lines: &[String]) -> Vec<Vec<char>> {
     lines.iter().map(|s| s.chars().collect()).collect()
 }
 
-fn transpose2<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
+pub fn transpose2<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
     assert!(!v.is_empty());
     let len = v[0].len();
     let mut iters: Vec<_> = v.into_iter().map(|n| n.into_iter()).collect();
@@ -22,31 +18,31 @@ fn transpose2<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
         .collect()
 }
 
-fn reverse<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
+pub fn reverse<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
     v.into_iter()
         .map(|row| row.into_iter().rev().collect())
         .collect()
 }
 
-fn flip<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
+pub fn flip<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
     v.into_iter()
         .map(|row| row.into_iter().collect())
         .rev()
         .collect()
 }
 
-fn count_horizontal(grid: &Vec<Vec<char>>, needle: &str) -> usize {
+pub fn count_horizontal(grid: &[Vec<char>], needle: &str) -> usize {
     grid.iter()
         .map(|row| {
             (0..=row.len() - needle.len())
                 .filter(|i| {
-                    row.into_iter()
+                    row.iter()
                         .skip(*i)
                         .take(needle.len())
                         .zip(needle.chars())
                         .all(|(x, y)| *x == y)
                         || row
-                            .into_iter()
+                            .iter()
                             .skip(*i)
                             .take(needle.len())
                             .rev()
@@ -58,7 +54,7 @@ fn count_horizontal(grid: &Vec<Vec<char>>, needle: &str) -> usize {
         .sum()
 }
 
-fn count_diagonal(grid: &Vec<Vec<char>>, needle: &str) -> usize {
+pub fn count_diagonal(grid: &[Vec<char>], needle: &str) -> usize {
     (0..=grid.len() - needle.len())
         .map(|y| {
             (0..=grid[0].len() - needle.len())
@@ -72,7 +68,7 @@ fn count_diagonal(grid: &Vec<Vec<char>>, needle: &str) -> usize {
         .sum()
 }
 
-fn print_grid(grid: &Vec<Vec<char>>) {
+pub fn print_grid(grid: &[Vec<char>]) {
     println!(
         "{}",
         grid.iter()
@@ -81,11 +77,11 @@ fn print_grid(grid: &Vec<Vec<char>>) {
     );
 }
 
-fn part1(grid: &Vec<Vec<char>>) -> usize {
+pub fn part1(grid: &[Vec<char>]) -> usize {
     let needle = "XMAS";
-    let transposed = transpose2(grid.clone());
-    let reversed = reverse(grid.clone());
-    let flipped = flip(grid.clone());
+    let transposed = transpose2(grid.to_vec());
+    let reversed = reverse(grid.to_vec());
+    let flipped = flip(grid.to_vec());
     let flipped_reversed = flip(reversed.clone());
     count_horizontal(grid, needle)
         + count_horizontal(&transposed, needle)
@@ -95,7 +91,7 @@ fn part1(grid: &Vec<Vec<char>>) -> usize {
         + count_diagonal(&flipped_reversed, needle)
 }
 
-fn count_xmas(grid: &Vec<Vec<char>>) -> usize {
+pub fn count_xmas(grid: &[Vec<char>]) -> usize {
     let needle = "MAS";
     (1..grid.len() - 1)
         .map(|y| {
@@ -133,26 +129,6 @@ fn count_xmas(grid: &Vec<Vec<char>>) -> usize {
         })
         .sum()
 }
-fn part2(grid: &Vec<Vec<char>>) -> usize {
+pub fn part2(grid: &[Vec<char>]) -> usize {
     count_xmas(grid)
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let parsed = parse(&lines);
-    print_grid(&parsed);
-
-    println!("Part 1: {}", part1(&parsed));
-    println!("Part 2: {}", part2(&parsed));
-}
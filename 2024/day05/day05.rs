@@ -1,14 +1,11 @@
 use std::cmp::Ordering;
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type PageOrdering = (usize, usize);
+pub type PageOrdering = (usize, usize);
 
-fn parse(lines: &Vec<String>) -> (HashSet<PageOrdering>, Vec<Vec<usize>>) {
+pub fn parse(lines: &[String]) -> (HashSet<PageOrdering>, Vec<Vec<usize>>) {
     let (rules, updates) = lines.split(String::is_empty).collect_tuple().unwrap();
     (
         rules
@@ -33,7 +30,7 @@ fn parse(lines: &Vec<String>) -> (HashSet<PageOrdering>, Vec<Vec<usize>>) {
     )
 }
 
-fn update_is_ordered(update: &Vec<usize>, rules: &HashSet<PageOrdering>) -> bool {
+pub fn update_is_ordered(update: &[usize], rules: &HashSet<PageOrdering>) -> bool {
     update.iter().enumerate().all(|(i, left_page)| {
         !update
             .iter()
@@ -42,7 +39,7 @@ fn update_is_ordered(update: &Vec<usize>, rules: &HashSet<PageOrdering>) -> bool
     })
 }
 
-fn part1(rules: &HashSet<PageOrdering>, updates: &Vec<Vec<usize>>) -> usize {
+pub fn part1(rules: &HashSet<PageOrdering>, updates: &[Vec<usize>]) -> usize {
     updates
         .iter()
         .filter(|update| update_is_ordered(update, rules))
@@ -50,7 +47,7 @@ fn part1(rules: &HashSet<PageOrdering>, updates: &Vec<Vec<usize>>) -> usize {
         .sum()
 }
 
-fn part2(rules: &HashSet<PageOrdering>, updates: &Vec<Vec<usize>>) -> usize {
+pub fn part2(rules: &HashSet<PageOrdering>, updates: &[Vec<usize>]) -> usize {
     updates
         .iter()
         .filter(|update| !update_is_ordered(update, rules))
@@ -70,22 +67,3 @@ fn part2(rules: &HashSet<PageOrdering>, updates: &Vec<Vec<usize>>) -> usize {
         .map(|update| update[update.len() / 2])
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let (rules, updates) = parse(&lines);
-
-    println!("Part 1: {}", part1(&rules, &updates));
-    println!("Part 2: {}", part2(&rules, &updates));
-}
@@ -1,17 +1,14 @@
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Grid = Vec<Vec<char>>;
+pub type Grid = Vec<Vec<char>>;
 
-fn parse(lines: &Vec<String>) -> Vec<Vec<char>> {
+pub fn parse(lines: &[String]) -> Vec<Vec<char>> {
     lines.iter().map(|line| line.chars().collect()).collect()
 }
 
-fn print_grid(grid: &Grid, visited_poss: &HashSet<(usize, usize)>) {
+pub fn print_grid(grid: &Grid, visited_poss: &HashSet<(usize, usize)>) {
     println!(
         "{}",
         grid.iter()
@@ -29,7 +26,7 @@ fn print_grid(grid: &Grid, visited_poss: &HashSet<(usize, usize)>) {
     );
 }
 
-fn find_start_pos(grid: &Grid) -> (usize, usize) {
+pub fn find_start_pos(grid: &Grid) -> (usize, usize) {
     grid.iter()
         .enumerate()
         .filter_map(|(y, row)| {
@@ -44,12 +41,14 @@ fn find_start_pos(grid: &Grid) -> (usize, usize) {
         .expect("couldn't find start pos")
 }
 
-fn walk_grid(
+pub type Visited = HashSet<((usize, usize), (isize, isize))>;
+
+pub fn walk_grid(
     grid: &Grid,
     start_pos: (usize, usize),
     test_pos: Option<(usize, usize)>,
-) -> (HashSet<((usize, usize), (isize, isize))>, bool) {
-    let mut pos = start_pos.clone();
+) -> (Visited, bool) {
+    let mut pos = start_pos;
     let mut dir: (isize, isize) = (0, -1);
 
     let height = grid.len() as isize;
@@ -69,8 +68,8 @@ fn walk_grid(
             if visited_poss.contains(&(new_pos, dir)) {
                 break;
             }
-            if grid[new_pos.1 as usize][new_pos.0 as usize] == '#' || Some(new_pos) == test_pos {
-                dir = (dir.1 * -1, dir.0);
+            if grid[new_pos.1][new_pos.0] == '#' || Some(new_pos) == test_pos {
+                dir = (-dir.1, dir.0);
                 visited_poss.insert((pos, dir));
             } else {
                 pos = new_pos;
@@ -84,7 +83,7 @@ fn walk_grid(
     (visited_poss, left_bounds)
 }
 
-fn part1(grid: &Grid) -> usize {
+pub fn part1(grid: &Grid) -> usize {
     let start_pos = find_start_pos(grid);
 
     let (visited_poss, _) = walk_grid(grid, start_pos, None);
@@ -96,7 +95,7 @@ fn part1(grid: &Grid) -> usize {
     visited_poss.len()
 }
 
-fn part2(grid: &Grid) -> usize {
+pub fn part2(grid: &Grid) -> usize {
     let start_pos = find_start_pos(grid);
 
     let (visited_poss, _) = walk_grid(grid, start_pos, None);
@@ -111,22 +110,3 @@ fn part2(grid: &Grid) -> usize {
         .filter(|(x, y)| !walk_grid(grid, start_pos, Some((*x, *y))).1)
         .count()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let grid = parse(&lines);
-
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
-}
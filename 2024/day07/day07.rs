@@ -1,32 +1,29 @@
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-fn parse(lines: &Vec<String>) -> HashMap<usize, Vec<usize>> {
+pub fn parse(lines: &[String]) -> HashMap<usize, Vec<usize>> {
     lines
         .iter()
         .filter_map(|line| {
-            line.split(": ").collect_tuple().and_then(|(k, v)| {
-                Some((
+            line.split(": ").collect_tuple().map(|(k, v)| {
+                (
                     k.parse::<usize>().expect("Couldn't get value"),
                     v.split_whitespace()
                         .map(str::parse::<usize>)
                         .filter_map(Result::ok)
                         .collect(),
-                ))
+                )
             })
         })
         .collect()
 }
 
-fn equation_works((result, operands): &(&usize, &Vec<usize>), operand_count: usize) -> bool {
+pub fn equation_works((result, operands): &(&usize, &Vec<usize>), operand_count: usize) -> bool {
     (0..operand_count.pow(operands.len() as u32 - 1))
         .find(|possibility| {
             (1..operands.len()).fold(
-                operands.first().expect("Couldn't get init operand").clone(),
+                *operands.first().expect("Couldn't get init operand"),
                 |acc, ind| {
                     let operand = operands[ind];
                     let op_type =
@@ -46,7 +43,7 @@ fn equation_works((result, operands): &(&usize, &Vec<usize>), operand_count: usi
         .is_some()
 }
 
-fn part1(equations: &HashMap<usize, Vec<usize>>) -> usize {
+pub fn part1(equations: &HashMap<usize, Vec<usize>>) -> usize {
     equations
         .iter()
         .filter(|equation| equation_works(equation, 2))
@@ -54,29 +51,10 @@ fn part1(equations: &HashMap<usize, Vec<usize>>) -> usize {
         .sum()
 }
 
-fn part2(equations: &HashMap<usize, Vec<usize>>) -> usize {
+pub fn part2(equations: &HashMap<usize, Vec<usize>>) -> usize {
     equations
         .iter()
         .filter(|equation| equation_works(equation, 3))
         .map(|(result, _)| result)
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let equations = parse(&lines);
-
-    println!("Part 1: {}", part1(&equations));
-    println!("Part 2: {}", part2(&equations));
-}
@@ -1,18 +1,15 @@
 use std::cmp::max;
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Antennae = HashMap<char, Vec<(isize, isize)>>;
+pub type Antennae = HashMap<char, Vec<(isize, isize)>>;
 #[derive(Debug)]
-struct Grid {
-    antennae: Antennae,
-    dims: (isize, isize),
+pub struct Grid {
+    pub antennae: Antennae,
+    pub dims: (isize, isize),
 }
-fn parse(lines: &Vec<String>) -> Grid {
+pub fn parse(lines: &[String]) -> Grid {
     Grid {
         antennae: lines
             .iter()
@@ -38,7 +35,7 @@ fn parse(lines: &Vec<String>) -> Grid {
     }
 }
 
-fn calculate_antinodes(
+pub fn calculate_antinodes(
     grid: &Grid,
     antinode_order: isize,
     include_antenna_itself: bool,
@@ -52,12 +49,11 @@ fn calculate_antinodes(
                     .iter()
                     .cartesian_product(positions.iter())
                     .filter(|(p1, p2)| *p2 != *p1 || include_antenna_itself)
-                    .map(|(p1, p2)| {
+                    .flat_map(|(p1, p2)| {
                         let diff = (p1.0 - p2.0, p1.1 - p2.1);
                         (1isize..=antinode_order)
                             .map(move |i| (p1.0 + diff.0 * i, p1.1 + diff.1 * i))
                     })
-                    .flatten()
                     .filter(|(x, y)| *x >= 0 && *y >= 0)
                     .filter(|(x, y)| *x < grid.dims.0 && *y < grid.dims.1)
                     .collect(),
@@ -66,41 +62,20 @@ fn calculate_antinodes(
         .collect()
 }
 
-fn part1(grid: &Grid) -> usize {
-    let antinodes = calculate_antinodes(&grid, 1, false);
+pub fn part1(grid: &Grid) -> usize {
+    let antinodes = calculate_antinodes(grid, 1, false);
     antinodes
-        .iter()
-        .map(|(_, positions)| positions.iter())
-        .flatten()
+        .values()
+        .flat_map(|positions| positions.iter())
         .unique()
         .count()
 }
 
-fn part2(grid: &Grid) -> usize {
-    let antinodes = calculate_antinodes(&grid, max(grid.dims.0, grid.dims.1), true);
+pub fn part2(grid: &Grid) -> usize {
+    let antinodes = calculate_antinodes(grid, max(grid.dims.0, grid.dims.1), true);
     antinodes
-        .iter()
-        .map(|(_, positions)| positions.iter())
-        .flatten()
+        .values()
+        .flat_map(|positions| positions.iter())
         .unique()
         .count()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let grid = parse(&lines);
-
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
-}
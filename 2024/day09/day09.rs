@@ -1,20 +1,17 @@
 use std::collections::{HashSet, VecDeque};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-fn parse(lines: &Vec<String>) -> Vec<Vec<usize>> {
+pub fn parse(lines: &[String]) -> Vec<Vec<usize>> {
     lines
         .iter()
         .map(|line| {
             line.chars()
-                .map(|c| {
-                    if ('0'..='9').contains(&c) {
+                .map(|c: char| {
+                    if c.is_ascii_digit() {
                         c
                     } else {
-                        ('0' as u8 + 20) as char
+                        (b'0' + 20) as char
                     }
                 })
                 .map(|c| c as usize - '0' as usize)
@@ -23,7 +20,7 @@ fn parse(lines: &Vec<String>) -> Vec<Vec<usize>> {
         .collect()
 }
 
-fn print_grid(grid: &Vec<Vec<usize>>) {
+pub fn print_grid(grid: &[Vec<usize>]) {
     println!(
         "{}",
         grid.iter()
@@ -39,48 +36,41 @@ fn print_grid(grid: &Vec<Vec<usize>>) {
     );
 }
 
-fn find_trailheads(grid: &Vec<Vec<usize>>) -> HashSet<(usize, usize)> {
+pub fn find_trailheads(grid: &[Vec<usize>]) -> HashSet<(usize, usize)> {
     grid.iter()
         .enumerate()
-        .map(|(y, row)| {
+        .flat_map(|(y, row)| {
             row.iter()
                 .enumerate()
                 .filter(|(_, c)| **c == 0)
                 .map(move |(x, _)| (x, y))
         })
-        .flatten()
         .collect()
 }
 
-fn score_trailhead(grid: &Vec<Vec<usize>>, trailhead: &(usize, usize)) -> (usize, usize) {
+pub fn score_trailhead(grid: &[Vec<usize>], trailhead: &(usize, usize)) -> (usize, usize) {
     let mut nodes = [vec![*trailhead]]
         .into_iter()
         .collect::<VecDeque<Vec<(usize, usize)>>>();
     let mut visited_paths = HashSet::<Vec<(usize, usize)>>::new();
-    let mut score = 0;
     while let Some(path) = nodes.pop_back() {
         if let Some((x, y)) = path.last().cloned() {
             let node_val = grid[y][x];
             visited_paths.insert(path.clone());
-            if node_val == 9 {
-                score += 1;
-            }
 
             let mut new_paths = (-1..=1isize)
                 .cartesian_product(-1..=1isize)
                 .filter(|(x, y)| x != y && *x != -y)
-                .map(|(deltax, deltay)| {
-                    (x as isize + deltax as isize, y as isize + deltay as isize)
-                })
+                .map(|(deltax, deltay)| (x as isize + deltax, y as isize + deltay))
                 .filter(|(x, y)| *x >= 0 && *y >= 0)
                 .map(|(x, y)| (x as usize, y as usize))
                 .filter(|(x, y)| *y < grid.len() && *x < grid[0].len())
                 .filter(|(x, y)| node_val + 1 == grid[*y][*x])
-                .map(|p| path.iter().cloned().chain([p].into_iter()).collect_vec())
+                .map(|p| path.iter().cloned().chain([p]).collect_vec())
                 .collect::<Vec<Vec<(usize, usize)>>>();
 
             new_paths.retain(|node| !visited_paths.contains(node.as_slice()));
-            nodes.extend(new_paths.into_iter());
+            nodes.extend(new_paths);
         }
     }
 
@@ -99,7 +89,7 @@ fn score_trailhead(grid: &Vec<Vec<usize>>, trailhead: &(usize, usize)) -> (usize
     )
 }
 
-fn part1(grid: &Vec<Vec<usize>>) -> usize {
+pub fn part1(grid: &[Vec<usize>]) -> usize {
     let trailheads = find_trailheads(grid);
     trailheads
         .iter()
@@ -107,31 +97,10 @@ fn part1(grid: &Vec<Vec<usize>>) -> usize {
         .sum()
 }
 
-fn part2(grid: &Vec<Vec<usize>>) -> usize {
+pub fn part2(grid: &[Vec<usize>]) -> usize {
     let trailheads = find_trailheads(grid);
     trailheads
         .iter()
         .map(|trailhead| score_trailhead(grid, trailhead).1)
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    let grid = parse(&lines);
-    print_grid(&grid);
-
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
-}
@@ -1,10 +1,6 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
-
 use indicatif::ProgressBar;
 
-fn parse(lines: &Vec<String>) -> Vec<usize> {
+pub fn parse(lines: &[String]) -> Vec<usize> {
     lines
         .first()
         .expect("Couldn't get frist line")
@@ -13,18 +9,18 @@ fn parse(lines: &Vec<String>) -> Vec<usize> {
         .collect()
 }
 
-fn do_stuff(stones: &Vec<usize>, iters: usize) -> usize {
+pub fn do_stuff(stones: &[usize], iters: usize) -> usize {
     let progress = ProgressBar::new(iters as u64);
     let a = (0..iters)
-        .fold(stones.clone(), |stones, _| {
+        .fold(stones.to_vec(), |stones, _| {
             //dbg!(&stones);
             progress.inc(1);
             stones
                 .iter()
-                .map(|stone| {
+                .flat_map(|stone| {
                     let stone_str = stone.to_string();
                     if *stone == 0 {
-                        [1, usize::max_value()]
+                        [1, usize::MAX]
                     } else if stone_str.len() % 2 == 0 {
                         [
                             stone_str[0..(stone_str.len() / 2)]
@@ -35,39 +31,13 @@ fn do_stuff(stones: &Vec<usize>, iters: usize) -> usize {
                                 .expect("Couldn't parse right half"),
                         ]
                     } else {
-                        [*stone * 2024, usize::max_value()]
+                        [*stone * 2024, usize::MAX]
                     }
                 })
-                .flatten()
-                .filter(|x| *x != usize::max_value())
+                .filter(|x| *x != usize::MAX)
                 .collect()
         })
         .len();
     progress.finish();
     a
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let parsed = parse(&lines);
-
-    println!("Part 1: {}", do_stuff(&parsed, 25));
-    println!(
-        "Part 2: {}",
-        parsed
-            .iter()
-            .map(|c| do_stuff(&vec![*c], 75))
-            .sum::<usize>()
-    );
-}
@@ -1,40 +1,36 @@
 use std::collections::{HashMap, HashSet};
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
-type Grid = Vec<Vec<char>>;
-fn parse(lines: &Vec<String>) -> Vec<Vec<char>> {
+pub type Grid = Vec<Vec<char>>;
+pub fn parse(lines: &[String]) -> Vec<Vec<char>> {
     lines.iter().map(|line| line.chars().collect()).collect()
 }
 
-fn print_grid(grid: &Grid) {
+pub fn print_grid(grid: &Grid) {
     println!("{}", grid.iter().map(|row| row.iter().join("")).join("\n"));
 }
 
-fn adjacent_nodes((x, y): &(isize, isize)) -> Vec<(isize, isize)> {
+pub fn adjacent_nodes((x, y): &(isize, isize)) -> Vec<(isize, isize)> {
     (-1..=1isize)
         .cartesian_product(-1..=1isize)
         .filter(|(deltax, deltay)| deltax.abs() - deltay.abs() != 0)
         .map(move |(deltax, deltay)| (x + deltax, y + deltay))
         .collect()
 }
-fn plot_perimeter_nodes(plot: &HashSet<(isize, isize)>) -> Vec<(isize, isize)> {
+pub fn plot_perimeter_nodes(plot: &HashSet<(isize, isize)>) -> Vec<(isize, isize)> {
     plot.iter()
-        .map(|pos| {
+        .flat_map(|pos| {
             adjacent_nodes(pos)
                 .into_iter()
                 .filter(|v| !plot.contains(v))
         })
-        .flatten()
         .collect()
 }
 
-type Plots = Vec<HashSet<(isize, isize)>>;
+pub type Plots = Vec<HashSet<(isize, isize)>>;
 
-fn plot_terrain(grid: &Grid) -> Plots {
+pub fn plot_terrain(grid: &Grid) -> Plots {
     let mut plots: Vec<HashSet<(isize, isize)>> = vec![];
     let (width, height) = (grid.first().unwrap().len(), grid.len());
     //let mut visited_notes = HashSet::<(isize, isize)>::new();
@@ -53,7 +49,7 @@ fn plot_terrain(grid: &Grid) -> Plots {
         loop {
             let additional_plot_cells = plot
                 .iter()
-                .map(|pos| {
+                .flat_map(|pos| {
                     adjacent_nodes(pos)
                         .into_iter()
                         .filter(|v| !plot.contains(v))
@@ -63,9 +59,8 @@ fn plot_terrain(grid: &Grid) -> Plots {
                         .filter(|(testx, testy)| grid[*testy][*testx] == plotval)
                         .map(|(testx, testy)| (testx as isize, testy as isize))
                 })
-                .flatten()
                 .collect_vec();
-            if additional_plot_cells.len() == 0 {
+            if additional_plot_cells.is_empty() {
                 break;
             }
             plot.extend(additional_plot_cells.iter());
@@ -75,18 +70,18 @@ fn plot_terrain(grid: &Grid) -> Plots {
     plots
 }
 
-fn part1(plots: &Plots) -> usize {
+pub fn part1(plots: &Plots) -> usize {
     plots
         .iter()
         .map(|plot| {
             let area = plot.len();
-            let perimeter = plot_perimeter_nodes(&plot);
+            let perimeter = plot_perimeter_nodes(plot);
             area * perimeter.len()
         })
         .sum()
 }
 
-fn part2(plots: &Plots) -> usize {
+pub fn part2(plots: &Plots) -> usize {
     plots
         .iter()
         .map(|plot| {
@@ -122,9 +117,8 @@ fn part2(plots: &Plots) -> usize {
                         .map(|i| (cur_node.0 + deltax * i, cur_node.1 + deltay * i))
                         .take_while(|test_dir_node| {
                             //if !visited_dirs.contains(&(test_dir_node, (deltax, deltay))) {
-                            perimeter.contains_key(&test_dir_node)
-                                && visited_counts.get(&test_dir_node)
-                                    != perimeter.get(&test_dir_node)
+                            perimeter.contains_key(test_dir_node)
+                                && visited_counts.get(test_dir_node) != perimeter.get(test_dir_node)
                             //visited_counts.get(&test_dir_node) != Some(cur_count)
                             /*
                             {
@@ -146,9 +140,9 @@ fn part2(plots: &Plots) -> usize {
                                         None
                                     }
                                      */
-                                    perimeter.contains_key(&test_dir_node)
-                                        && visited_counts.get(&test_dir_node)
-                                            != perimeter.get(&test_dir_node)
+                                    perimeter.contains_key(test_dir_node)
+                                        && visited_counts.get(test_dir_node)
+                                            != perimeter.get(test_dir_node)
                                     //visited_counts.get(&test_dir_node) != Some(cur_count)
                                 }),
                         )
@@ -163,31 +157,6 @@ fn part2(plots: &Plots) -> usize {
             sides
         })
         .zip(plots.iter().map(|plot| plot.len()))
-        .map(|(sides, area)| {
-            //println!("{} * {} = {}", sides, area, sides * area);
-            (sides, area)
-        })
         .map(|(sides, area)| sides * area)
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let grid = parse(&lines);
-    let plots = plot_terrain(&grid);
-
-    print_grid(&grid);
-    println!("Part 1: {}", part1(&plots));
-    println!("Part 2: {}", part2(&plots));
-}
@@ -0,0 +1,26 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead};
+
+use day11::{parse, part1, part2, plot_terrain, print_grid};
+
+fn main() {
+    if env::args().count() != 2 {
+        return println!(
+            "Usage: {} [path/to/input_file]",
+            env::args().next().expect("Couldn't get executable name")
+        );
+    }
+    let input_name: String = env::args().nth(1).expect("First argument");
+    let f = File::open(input_name).expect("Couldn't open input file");
+    let lines: Vec<String> = io::BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+    let grid = parse(&lines);
+    let plots = plot_terrain(&grid);
+
+    print_grid(&grid);
+    println!("Part 1: {}", part1(&plots));
+    println!("Part 2: {}", part2(&plots));
+}
@@ -1,6 +1,3 @@
-use std::env;
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::ops::RangeInclusive;
 
 use itertools::Itertools;
@@ -10,14 +7,14 @@ use regex::Regex;
 
 static NUM_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
 
-struct Machine {
-    a: (usize, usize),
-    b: (usize, usize),
-    prize: (usize, usize),
+pub struct Machine {
+    pub a: (usize, usize),
+    pub b: (usize, usize),
+    pub prize: (usize, usize),
 }
 
-fn parse(lines: &Vec<String>) -> Vec<Machine> {
-    let extract_vec = |line: &String| {
+pub fn parse(lines: &[String]) -> Vec<Machine> {
+    let extract_vec = |line: &str| {
         NUM_RE
             .captures_iter(line)
             .take(2)
@@ -41,7 +38,10 @@ fn parse(lines: &Vec<String>) -> Vec<Machine> {
         .collect()
 }
 
-fn solve_machine(machine: &Machine, search_range: RangeInclusive<usize>) -> Option<(usize, usize)> {
+pub fn solve_machine(
+    machine: &Machine,
+    search_range: RangeInclusive<usize>,
+) -> Option<(usize, usize)> {
     search_range
         .clone()
         .cartesian_product(search_range)
@@ -53,7 +53,7 @@ fn solve_machine(machine: &Machine, search_range: RangeInclusive<usize>) -> Opti
         })
 }
 
-fn solve_machine_with_math(machine: &Machine) -> Option<(usize, usize)> {
+pub fn solve_machine_with_math(machine: &Machine) -> Option<(usize, usize)> {
     let buttons_matrix = Matrix2::new(
         machine.a.0 as f64,
         machine.b.0 as f64,
@@ -79,15 +79,15 @@ fn solve_machine_with_math(machine: &Machine) -> Option<(usize, usize)> {
     None
 }
 
-fn part1(machines: &Vec<Machine>) -> usize {
+pub fn part1(machines: &[Machine]) -> usize {
     machines
         .iter()
-        .filter_map(|machine| solve_machine_with_math(machine))
+        .filter_map(solve_machine_with_math)
         .map(|(a, b)| 3 * a + b)
         .sum()
 }
 
-fn part2(mut machines: Vec<Machine>) -> usize {
+pub fn part2(mut machines: Vec<Machine>) -> usize {
     let p2_factor = 10000000000000;
     machines.iter_mut().for_each(|machine| {
         machine.prize.0 += p2_factor;
@@ -96,26 +96,7 @@ fn part2(mut machines: Vec<Machine>) -> usize {
 
     machines
         .iter()
-        .filter_map(|machine| solve_machine_with_math(machine))
+        .filter_map(solve_machine_with_math)
         .map(|(a, b)| 3 * a + b)
         .sum()
 }
-
-fn main() {
-    if env::args().count() != 2 {
-        return println!(
-            "Usage: {} [path/to/input_file]",
-            env::args().next().expect("Couldn't get executable name")
-        );
-    }
-    let input_name: String = env::args().skip(1).next().expect("First argument");
-    let f = File::open(input_name).expect("Couldn't open input file");
-    let lines: Vec<String> = io::BufReader::new(f)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    let machines = parse(&lines);
-
-    println!("Part 1: {}", part1(&machines));
-    println!("Part 2: {}", part2(machines));
-}